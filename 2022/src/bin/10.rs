@@ -1,20 +1,30 @@
-pub fn part_one(input: &str) -> Option<i32> {
-    let signals = get_signals(input);
+use advent_of_code::{
+    error::{AocError, AocResult},
+    parse::{Cursor, ParseResult},
+    vm::{Flow, InstructionSet, Machine},
+};
+
+pub fn part_one(input: &str) -> AocResult<i32> {
+    let signals = get_signals(input)?;
 
     let indexes: Vec<i32> = vec![20, 60, 100, 140, 180, 220];
     let mut sum: i32 = 0;
     for idx in indexes {
-        sum += idx * signals[idx as usize - 1];
+        let signal = signals
+            .get(idx as usize - 1)
+            .ok_or_else(|| AocError::assumption("the program runs for 220 cycles"))?;
+        sum += idx * signal;
     }
 
-    Some(sum)
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    let signals = get_signals(input);
+pub fn part_two(input: &str) -> AocResult<String> {
+    let signals = get_signals(input)?;
 
     let mut grid = [['.' as char; 40]; 6];
-    for (i, signal) in signals.iter().enumerate() {
+    // The screen is drawn once, in 240 cycles
+    for (i, signal) in signals.iter().take(240).enumerate() {
         let x = i % 40;
         let y = i / 40;
         if (x as i32 - signal).abs() < 2 {
@@ -30,25 +40,71 @@ pub fn part_two(input: &str) -> Option<String> {
         result.push('\n');
     }
 
-    Some(result)
+    Ok(result)
 }
 
-fn get_signals(input: &str) -> Vec<i32> {
-    let mut signals: Vec<i32> = Vec::new();
-    let mut x: i32 = 1;
-    signals.push(x);
-
-    for line in input.lines() {
-        if line.contains("noop") {
-            signals.push(x);
-        } else {
-            let n: i32 = line.split_once(" ").unwrap().1.parse().unwrap();
-            signals.push(x);
-            x += n;
-            signals.push(x);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+/// The CPU, with X as its only register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cpu;
+
+impl InstructionSet for Cpu {
+    type Word = Instruction;
+    type Instruction = Instruction;
+    type Registers = i32;
+    type Output = ();
+
+    fn decode(program: &[Instruction], ip: usize) -> Option<(Instruction, usize)> {
+        program.get(ip).map(|instruction| (*instruction, 1))
+    }
+
+    fn cycles(instruction: &Instruction) -> usize {
+        match instruction {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Self>) -> Flow {
+        if let Instruction::Addx(n) = instruction {
+            machine.registers += n;
         }
+        Flow::Next
     }
-    signals
+}
+
+fn parse_program(input: &str) -> ParseResult<Vec<Instruction>> {
+    let mut cursor = Cursor::new(input);
+    let mut program = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let start = cursor;
+        match cursor.word()? {
+            "noop" => program.push(Instruction::Noop),
+            "addx" => {
+                cursor.skip_spaces();
+                program.push(Instruction::Addx(cursor.integer()?));
+            }
+            word => return Err(start.error(format!("Unknown instruction {:?}", word))),
+        }
+        cursor.skip_whitespace();
+    }
+    Ok(program)
+}
+
+/// Value of X during every cycle.
+fn get_signals(input: &str) -> ParseResult<Vec<i32>> {
+    let program = parse_program(input)?;
+
+    let mut signals: Vec<i32> = Vec::new();
+    let mut cpu: Machine<Cpu> = Machine::new(program, 1);
+    cpu.run_with(|_, cpu| signals.push(cpu.registers));
+    Ok(signals)
 }
 
 fn main() {
@@ -64,7 +120,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 10);
-        assert_eq!(part_one(&input), Some(13140));
+        assert_eq!(part_one(&input), Ok(13140));
     }
 
     #[test]
@@ -73,6 +129,15 @@ mod tests {
         let expected: String = String::from(
             "##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....\n",
         );
-        assert_eq!(part_two(&input), Some(expected));
+        assert_eq!(part_two(&input), Ok(expected));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = parse_program("noop\naddx 3\naddx x\n").unwrap_err();
+        assert_eq!((3, 6), (error.line, error.column));
+        let error = parse_program("noop\nmul 2\n").unwrap_err();
+        assert_eq!((2, 1), (error.line, error.column));
+        assert!(matches!(part_one("noop\n"), Err(AocError::Assumption(_))));
     }
 }
@@ -1,25 +1,43 @@
-use num::integer::lcm;
-
-#[derive(Debug, Clone)]
+use advent_of_code::{
+    error::{AocError, AocResult},
+    math, parse,
+};
+use aoc_derive::AocParse;
+
+#[derive(Debug, Clone, AocParse)]
+#[aoc(format = "Monkey {_}:
+  Starting items: {items:,}
+  Operation: new = old {operation} {operand}
+  Test: divisible by {divisible}
+    If true: throw to monkey {throw_true}
+    If false: throw to monkey {throw_false}")]
 struct Monkey {
     items: Vec<u64>,
     operation: char,
-    operand: String,
+    operand: Operand,
     divisible: u32,
     throw_true: usize,
     throw_false: usize,
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    Some(solve(input, 20, true))
+#[derive(Debug, Clone, Copy, AocParse)]
+enum Operand {
+    #[aoc(format = "old")]
+    Old,
+    #[aoc(format = "{0}")]
+    Value(u64),
+}
+
+pub fn part_one(input: &str) -> AocResult<u64> {
+    solve(input, 20, true)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    Some(solve(input, 10000, false))
+pub fn part_two(input: &str) -> AocResult<u64> {
+    solve(input, 10000, false)
 }
 
-fn solve(input: &str, rounds: u32, part1: bool) -> u64 {
-    let mut monkeys = parse_input(input);
+fn solve(input: &str, rounds: u32, part1: bool) -> AocResult<u64> {
+    let mut monkeys = parse_input(input)?;
 
     let mut inspections: Vec<u64> = Vec::new();
     for _ in &monkeys {
@@ -28,9 +46,8 @@ fn solve(input: &str, rounds: u32, part1: bool) -> u64 {
 
     // For part2, to "keep the worry levels acceptable" we modulo by the lowest common multiple
     // of the "divisible by" numbers.
-    let base = monkeys
-        .iter()
-        .fold(1, |acc, m| lcm(acc, m.divisible as u64));
+    let base = math::lcm_all(monkeys.iter().map(|m| m.divisible as u64))
+        .ok_or(AocError::assumption("the divisors have a 64 bits LCM"))?;
 
     for _ in 0..rounds {
         for i in 0..monkeys.len() {
@@ -40,9 +57,9 @@ fn solve(input: &str, rounds: u32, part1: bool) -> u64 {
             for item in monkey.items.iter() {
                 inspections[i] += 1;
                 // Update worry level
-                let operand: u64 = match monkey.operand.as_str() {
-                    "old" => *item,
-                    _ => monkey.operand.parse().unwrap(),
+                let operand: u64 = match monkey.operand {
+                    Operand::Old => *item,
+                    Operand::Value(value) => value,
                 };
 
                 let new: u64 = match monkey.operation {
@@ -68,63 +85,14 @@ fn solve(input: &str, rounds: u32, part1: bool) -> u64 {
         }
     }
     inspections.sort();
-    inspections[inspections.len() - 1] * inspections[inspections.len() - 2]
+    Ok(inspections[inspections.len() - 1] * inspections[inspections.len() - 2])
 }
 
-// Very ugly parsing code
-fn parse_input(input: &str) -> Vec<Monkey> {
-    let mut monkeys: Vec<Monkey> = Vec::new();
-    let lines: Vec<&str> = input.lines().collect();
-
-    for chunk in lines.chunks(7) {
-        let items: Vec<u64> = chunk[1]
-            .rsplit_once(':')
-            .unwrap()
-            .1
-            .split(",")
-            .map(|i| i.trim().parse().unwrap())
-            .collect();
-
-        let op_line: Vec<&str> = chunk[2].split_ascii_whitespace().collect();
-        let operand = String::from(op_line[op_line.len() - 1]);
-        let operation = op_line[op_line.len() - 2].chars().collect::<Vec<char>>()[0];
-
-        let divisible: u32 = chunk[3]
-            .split_ascii_whitespace()
-            .collect::<Vec<&str>>()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        let throw_true = chunk[4]
-            .split_ascii_whitespace()
-            .collect::<Vec<&str>>()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        let throw_false = chunk[5]
-            .split_ascii_whitespace()
-            .collect::<Vec<&str>>()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        let monkey = Monkey {
-            items,
-            operation,
-            operand,
-            divisible,
-            throw_true,
-            throw_false,
-        };
-        monkeys.push(monkey);
-    }
-
-    monkeys
+fn parse_input(input: &str) -> AocResult<Vec<Monkey>> {
+    Ok(parse::blocks(input)
+        .into_iter()
+        .map(str::parse)
+        .collect::<Result<_, _>>()?)
 }
 
 fn main() {
@@ -140,12 +108,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 11);
-        assert_eq!(part_one(&input), Some(10605));
+        assert_eq!(part_one(&input), Ok(10605));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 11);
-        assert_eq!(part_two(&input), Some(2713310158));
+        assert_eq!(part_two(&input), Ok(2713310158));
     }
 }
@@ -1,128 +1,99 @@
-use std::collections::HashSet;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let (heights, _, s, e) = parse_input(input);
-    let distances = dijkstra(&heights, s, |c, n| n <= c + 1);
-    Some(distances[e.0][e.1] as u32)
+use advent_of_code::{
+    error::{AocError, AocResult},
+    parse::{Cursor, ParseResult},
+    search::bfs,
+};
+
+pub fn part_one(input: &str) -> AocResult<u32> {
+    let (heights, _, s, e) = parse_input(input)?;
+    let result = bfs(
+        s,
+        |&c| neighbours(&heights, c, |c, n| n <= c + 1),
+        |&c| c == e,
+    )
+    .ok_or(AocError::NoSolution)?;
+    Ok(result.cost as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (heights, size, _, e) = parse_input(input);
-    let (width, height) = size;
-
-    let distances = dijkstra(&heights, e, |c, n| c == 0 || n >= c - 1);
-
-    // Get min distance from 'a' point to 'E'
-    let mut dist = usize::MAX;
-    for y in 0..height {
-        for x in 0..width {
-            if heights[y][x] == 0 && distances[y][x] < dist {
-                dist = distances[y][x];
-            }
-        }
-    }
-
-    Some(dist as u32)
+pub fn part_two(input: &str) -> AocResult<u32> {
+    let (heights, _, _, e) = parse_input(input)?;
+
+    // Search backwards from 'E' to the closest 'a' point
+    let result = bfs(
+        e,
+        |&c| neighbours(&heights, c, |c, n| c == 0 || n >= c - 1),
+        |&(y, x)| heights[y][x] == 0,
+    )
+    .ok_or(AocError::NoSolution)?;
+    Ok(result.cost as u32)
 }
 
-fn dijkstra(
+fn neighbours(
     heights: &Vec<Vec<usize>>,
-    start: (usize, usize),
+    (y, x): (usize, usize),
     f: impl Fn(usize, usize) -> bool, // Filter for neighbours validity
-) -> Vec<Vec<usize>> {
+) -> Vec<(usize, usize)> {
     let height = heights.len();
     let width = heights[0].len();
 
-    // Initialize unvisited set
-    let mut unvisited: HashSet<(usize, usize)> = HashSet::new();
-    for y in 0..height {
-        for x in 0..width {
-            unvisited.insert((y, x));
-        }
-    }
-
-    // Initialize distances grid
-    let mut distances = vec![vec![usize::MAX as usize; width]; height];
-    distances[start.0][start.1] = 0;
-
-    // Main loop
-    let mut current = start;
-    while !unvisited.is_empty() {
-        let (y, x) = current;
-
-        // Get unvisited and valid neighbours
-        let neighbours: Vec<(usize, usize)> = [(-1, 0), (0, -1), (0, 1), (1, 0)]
-            .iter()
-            .map(|(my, mx)| ((y as i32 + *my), (x as i32 + *mx)))
-            .filter(|(my, mx)| *my >= 0 && *my < height as i32 && *mx >= 0 && *mx < width as i32)
-            .map(|(my, mx)| (my as usize, mx as usize))
-            .filter(|(my, mx)| unvisited.contains(&(*my, *mx)))
-            .filter(|(my, mx)| f(heights[y][x], heights[*my][*mx]))
-            .collect();
-
-        // Update distances
-        if distances[y][x] != usize::MAX {
-            for (ny, nx) in neighbours {
-                distances[ny][nx] = distances[ny][nx].min(distances[y][x] + 1);
-            }
-        }
-
-        // Remove current
-        unvisited.remove(&current);
-        if unvisited.is_empty() {
-            break;
-        }
-
-        // Set next point to check
-        current = *(unvisited
-            .iter()
-            .min_by_key(|(ky, kx)| distances[*ky][*kx])
-            .unwrap());
-    }
-
-    distances
+    [(-1, 0), (0, -1), (0, 1), (1, 0)]
+        .iter()
+        .map(|(my, mx)| ((y as i32 + *my), (x as i32 + *mx)))
+        .filter(|(my, mx)| *my >= 0 && *my < height as i32 && *mx >= 0 && *mx < width as i32)
+        .map(|(my, mx)| (my as usize, mx as usize))
+        .filter(|(my, mx)| f(heights[y][x], heights[*my][*mx]))
+        .collect()
 }
 
-fn parse_input(
-    input: &str,
-) -> (
+type HeightMap = (
     Vec<Vec<usize>>, // heights
     (usize, usize),  // size
     (usize, usize),  // 'S' coordinate
     (usize, usize),  // 'E' coordinate
-) {
-    // Read input file, map to heights from a to z
-    let input: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let heights: Vec<Vec<usize>> = input
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map({
-                    |col| match col {
-                        'S' => 0,
-                        'E' => 'z' as usize - 'a' as usize,
-                        c => *c as usize - 'a' as usize,
-                    }
-                })
-                .collect()
-        })
-        .collect();
-    let width = input[0].len();
-    let height = input.len();
-
-    let mut s_coord = (0, 0);
-    let mut e_coord = (0, 0);
-
-    for y in 0..height {
-        for x in 0..width {
-            match input[y][x] {
-                'S' => s_coord = (y, x),
-                'E' => e_coord = (y, x),
-                _ => (),
+);
+
+fn parse_input(input: &str) -> ParseResult<HeightMap> {
+    let mut cursor = Cursor::new(input);
+    let mut heights: Vec<Vec<usize>> = vec![];
+    let mut s_coord = None;
+    let mut e_coord = None;
+
+    // Heights from a to z, the start being at a and the end at z
+    while !cursor.is_empty() {
+        let y = heights.len();
+        let mut row = vec![];
+        while let Some(c) = cursor.peek().filter(|c| !matches!(c, '\n' | '\r')) {
+            let height = match c {
+                'S' => 0,
+                'E' => 25,
+                'a'..='z' => c as usize - 'a' as usize,
+                _ => return Err(cursor.error(format!("Unexpected {:?}", c))),
             };
+            let mark = match c {
+                'S' => Some(&mut s_coord),
+                'E' => Some(&mut e_coord),
+                _ => None,
+            };
+            if mark.is_some_and(|mark| mark.replace((y, row.len())).is_some()) {
+                return Err(cursor.error(format!("Duplicate {:?}", c)));
+            }
+            row.push(height);
+            cursor.char(c)?;
         }
+        if heights
+            .first()
+            .is_some_and(|first| first.len() != row.len())
+        {
+            return Err(cursor.error("Expected rows of the same length"));
+        }
+        heights.push(row);
+        cursor.line();
     }
-    (heights, (width, height), s_coord, e_coord)
+
+    let s_coord = s_coord.ok_or_else(|| cursor.error("Missing 'S'"))?;
+    let e_coord = e_coord.ok_or_else(|| cursor.error("Missing 'E'"))?;
+    let size = (heights[0].len(), heights.len());
+    Ok((heights, size, s_coord, e_coord))
 }
 
 fn main() {
@@ -138,12 +109,24 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 12);
-        assert_eq!(part_one(&input), Some(31));
+        assert_eq!(part_one(&input), Ok(31));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 12);
-        assert_eq!(part_two(&input), Some(29));
+        assert_eq!(part_two(&input), Ok(29));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = parse_input("Sab\nzy1\nxwE\n").unwrap_err();
+        assert_eq!((2, 3), (error.line, error.column));
+        let error = parse_input("Sab\nzyE\nxw\n").unwrap_err();
+        assert_eq!((3, 3), (error.line, error.column));
+        let error = parse_input("Sab\r\nzyx\r\n").unwrap_err();
+        assert_eq!((3, 1), (error.line, error.column));
+        let (heights, size, _, _) = parse_input("SbE\r\n").unwrap();
+        assert_eq!((vec![vec![0, 1, 25]], (3, 1)), (heights, size));
     }
 }
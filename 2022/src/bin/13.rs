@@ -1,63 +1,126 @@
-use serde_json::{json, Value};
-use std::cmp::Ordering;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let lines: Vec<Value> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| serde_json::from_str(l.trim()).unwrap())
-        .collect();
-
-    let result = lines
-        .chunks(2)
-        .map(|v| v.to_vec())
-        .enumerate()
-        .filter(|(_, v)| compare(&v[0], &v[1]).is_le())
-        .map(|(i, _)| i + 1)
-        .fold(0, |a, c| a + c);
+use std::{cmp::Ordering, fmt, slice, str::FromStr};
+
+use advent_of_code::{
+    error::AocResult,
+    parse::{Cursor, ParseError, ParseResult},
+};
+use itertools::Itertools;
 
-    Some(result as u32)
+/// A packet is an integer or a list of packets.
+///
+/// Packets are ordered as the puzzle compares them, and `PartialEq` follows
+/// that ordering rather than the structure: an integer equals the list
+/// holding only it, so `[[2]]` equals `2`.
+#[derive(Debug, Clone)]
+enum Packet {
+    Int(u32),
+    List(Vec<Packet>),
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut lines: Vec<Value> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| serde_json::from_str(l.trim()).unwrap())
-        .collect();
+impl Packet {
+    // Reads the packet straight from the input, without copying it
+    fn parse(cursor: &mut Cursor) -> ParseResult<Self> {
+        if cursor.peek() == Some('[') {
+            Ok(Packet::List(cursor.list('[', ']', Packet::parse)?))
+        } else {
+            Ok(Packet::Int(cursor.integer()?))
+        }
+    }
+}
 
-    lines.push(serde_json::from_str("[[2]]").unwrap());
-    lines.push(serde_json::from_str("[[6]]").unwrap());
+impl FromStr for Packet {
+    type Err = ParseError;
 
-    lines.sort_by(|a, b| compare(a, b));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let packet = Packet::parse(&mut cursor)?;
+        cursor.end()?;
+        Ok(packet)
+    }
+}
 
-    let first = lines.iter().position(|v| v.to_string() == "[[2]]").unwrap() + 1;
-    let second = lines.iter().position(|v| v.to_string() == "[[6]]").unwrap() + 1;
+// Lists are compared item by item, an integer compared to a list is a list of one
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+            (Packet::Int(_), Packet::List(b)) => slice::from_ref(self).cmp(b),
+            (Packet::List(a), Packet::Int(_)) => a.as_slice().cmp(slice::from_ref(other)),
+        }
+    }
+}
 
-    Some((first * second) as u32)
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-fn compare(a: &Value, b: &Value) -> std::cmp::Ordering {
-    let res = match (a, b) {
-        (Value::Number(a), Value::Number(b)) => a.as_u64().cmp(&b.as_u64()),
-        (Value::Array(a), Value::Array(b)) => {
-            if a.is_empty() || b.is_empty() {
-                return a.len().cmp(&b.len());
-            } else {
-                let res = compare(&json!(a[0]), &json!(b[0]));
-                if res.is_eq() {
-                    compare(&json!(a[1..]), &json!(b[1..]))
-                } else {
-                    res
-                }
-            }
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Packet {}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Int(value) => write!(f, "{}", value),
+            Packet::List(items) => write!(f, "[{}]", items.iter().join(",")),
         }
-        (Value::Number(a), Value::Array(b)) => compare(&json!(vec![a]), &json!(b)),
-        (Value::Array(a), Value::Number(b)) => compare(&json!(a), &json!(vec![b])),
-        _ => Ordering::Greater,
-    };
+    }
+}
 
-    res
+fn parse(input: &str) -> ParseResult<Vec<Packet>> {
+    let mut cursor = Cursor::new(input);
+    let mut packets = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        packets.push(Packet::parse(&mut cursor)?);
+        cursor.skip_whitespace();
+    }
+    Ok(packets)
+}
+
+/// The packets two by two, failing on a last packet without a pair.
+fn parse_pairs(input: &str) -> ParseResult<Vec<(Packet, Packet)>> {
+    let mut cursor = Cursor::new(input);
+    let mut pairs = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let left = Packet::parse(&mut cursor)?;
+        cursor.skip_whitespace();
+        if cursor.is_empty() {
+            return Err(cursor.error("Expected a second packet in the pair"));
+        }
+        let right = Packet::parse(&mut cursor)?;
+        pairs.push((left, right));
+        cursor.skip_whitespace();
+    }
+    Ok(pairs)
+}
+
+pub fn part_one(input: &str) -> AocResult<usize> {
+    let pairs = parse_pairs(input)?;
+
+    Ok(pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left <= right)
+        .map(|(i, _)| i + 1)
+        .sum())
+}
+
+pub fn part_two(input: &str) -> AocResult<usize> {
+    let packets = parse(input)?;
+    let dividers: [Packet; 2] = ["[[2]]".parse()?, "[[6]]".parse()?];
+
+    // Position of each divider once sorted, without sorting
+    let position = |divider: &Packet| packets.iter().filter(|p| *p < divider).count() + 1;
+    Ok(position(&dividers[0]) * (position(&dividers[1]) + 1))
 }
 
 fn main() {
@@ -70,15 +133,46 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_packet() {
+        let packet: Packet = "[1,[2,[3,[4,[5,6,7]]]],8,9]".parse().unwrap();
+        assert_eq!("[1,[2,[3,[4,[5,6,7]]]],8,9]", packet.to_string());
+        assert_eq!(
+            "[[],[[]]]",
+            "[[], [[]]]".parse::<Packet>().unwrap().to_string()
+        );
+
+        let error = "[1,[2.5]]".parse::<Packet>().unwrap_err();
+        assert_eq!((1, 6), (error.line, error.column));
+        assert!("[1,2".parse::<Packet>().is_err());
+        assert!("[-1]".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let packet = |s: &str| s.parse::<Packet>().unwrap();
+        assert!(packet("[[1],[2,3,4]]") < packet("[[1],4]"));
+        assert!(packet("[9]") > packet("[[8,7,6]]"));
+        assert!(packet("[[4,4],4,4]") < packet("[[4,4],4,4,4]"));
+        assert!(packet("[[[]]]") > packet("[[]]"));
+        assert_eq!(packet("[[2]]"), packet("2"));
+    }
+
+    #[test]
+    fn test_unpaired() {
+        let error = parse_pairs("[1]\n[2]\n\n[3]\n").unwrap_err();
+        assert_eq!((5, 1), (error.line, error.column));
+    }
+
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 13);
-        assert_eq!(part_one(&input), Some(13));
+        assert_eq!(part_one(&input), Ok(13));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 13);
-        assert_eq!(part_two(&input), Some(140));
+        assert_eq!(part_two(&input), Ok(140));
     }
 }
@@ -1,25 +1,25 @@
-// Given the size of the input, I decided to clean the input files manually
-// and just a have a list Sx,Sy,Bx,By
-
-use core::cmp::{max, min};
+use advent_of_code::{
+    error::{AocError, AocResult},
+    interval::{Interval, IntervalSet},
+    params::Params,
+    parse::{self, Cursor, ParseResult},
+};
 use std::collections::HashSet;
 
+/// Puzzle parameters, which the examples shrink.
+const PARAMS: &[&str] = &["row", "max_coord"];
+
 struct SensorBeacon {
     sensor: (i32, i32),
     beacon: (i32, i32),
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let row = 2000000; // Use 2000000 to solve, 10 for tests
-    let records = parse_input(input);
+pub fn part_one(input: &str, params: &Params) -> AocResult<u32> {
+    let row = params.get("row", 2000000)?;
+    let records = parse_input(input)?;
 
     let ranges = get_ranges(&records, row);
 
-    let mut sum: u32 = 0;
-    for (low, high) in ranges {
-        sum += (high - low).abs() as u32 + 1;
-    }
-
     let beacons = records
         .iter()
         .filter_map(|r| {
@@ -33,57 +33,45 @@ pub fn part_one(input: &str) -> Option<u32> {
         .iter()
         .count() as u32;
 
-    Some(sum - beacons)
+    Ok(ranges.len() as u32 - beacons)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let max_coord = 4000000; // Use 20 for tests, 4000000 for solution
-    let records = parse_input(input);
+pub fn part_two(input: &str, params: &Params) -> AocResult<u64> {
+    let max_coord = params.get("max_coord", 4000000)?;
+    let records = parse_input(input)?;
 
+    let bounds = Interval::inclusive(0, max_coord);
     for y in 0..=max_coord {
-        let mut ranges: Vec<(i32, i32)> = get_ranges(&records, y)
-            .iter()
-            .map(|(l, h)| (*l, *h))
-            .collect();
-        ranges.sort();
-
-        for i in 1..ranges.len() {
-            let (_, h1) = ranges[i - 1];
-            let (l2, _) = ranges[i];
-            if l2 > h1 + 1 && (h1 + 1 < max_coord) && (h1 + 1 > 0) {
-                return Some((h1 + 1) as u64 * 4000000 + y as u64);
-            }
+        let ranges = get_ranges(&records, y);
+
+        if let Some(gap) = ranges.complement(bounds).iter().next() {
+            return Ok(gap.start as u64 * 4000000 + y as u64);
         }
     }
-    None
+    Err(AocError::NoSolution)
 }
 
-fn parse_input(input: &str) -> Vec<SensorBeacon> {
-    let records: Vec<SensorBeacon> = input
+fn parse_input(input: &str) -> ParseResult<Vec<SensorBeacon>> {
+    input
         .lines()
         .map(|l| {
-            let vals: Vec<i32> = l.split(",").map(|v| v.parse().unwrap()).collect();
-            SensorBeacon {
-                sensor: (vals[1], vals[0]),
-                beacon: (vals[3], vals[2]),
+            // Sensor at x=Sx, y=Sy: closest beacon is at x=Bx, y=By
+            match parse::integers(l)?[..] {
+                [sx, sy, bx, by] => Ok(SensorBeacon {
+                    sensor: (sy, sx),
+                    beacon: (by, bx),
+                }),
+                _ => Err(Cursor::new(l).error("Expected 4 integers")),
             }
         })
-        .collect();
-    records
+        .collect()
 }
 
-fn get_ranges(records: &Vec<SensorBeacon>, row: i32) -> HashSet<(i32, i32)> {
-    let mut ranges: HashSet<(i32, i32)> = HashSet::new();
-    for sb in records {
-        match get_range(sb, row) {
-            Some(range) => add_range(&mut ranges, range),
-            None => (),
-        };
-    }
-    ranges
+fn get_ranges(records: &Vec<SensorBeacon>, row: i32) -> IntervalSet<i32> {
+    records.iter().filter_map(|sb| get_range(sb, row)).collect()
 }
 
-fn get_range(record: &SensorBeacon, row: i32) -> Option<(i32, i32)> {
+fn get_range(record: &SensorBeacon, row: i32) -> Option<Interval<i32>> {
     let sensor = record.sensor;
     let beacon = record.beacon;
     let x_steps = (sensor.1 - beacon.1).abs();
@@ -96,37 +84,17 @@ fn get_range(record: &SensorBeacon, row: i32) -> Option<(i32, i32)> {
     }
 
     let remaining = total_steps - y_delta;
-    Some((sensor.1 - remaining, sensor.1 + remaining))
-}
-
-fn add_range(ranges: &mut HashSet<(i32, i32)>, range: (i32, i32)) {
-    let mut curr = range;
-    ranges.insert(range);
-    loop {
-        let (curr_low, curr_high) = curr;
-        let overlapping: Option<&(i32, i32)> = ranges
-            .iter()
-            .filter(|(l, u)| (curr != (*l, *u)) && curr_low <= *u && curr_high >= *l)
-            .next();
-        if overlapping.is_none() {
-            break;
-        }
-        let overlapping = overlapping.unwrap().clone();
-
-        let (other_low, other_high) = overlapping;
-        ranges.remove(&overlapping);
-        ranges.remove(&curr);
-
-        let new_range = (min(curr_low, other_low), max(curr_high, other_high));
-        ranges.insert(new_range);
-        curr = new_range;
-    }
+    Some(Interval::inclusive(
+        sensor.1 - remaining,
+        sensor.1 + remaining,
+    ))
 }
 
 fn main() {
     let input = &advent_of_code::read_file("inputs", 15);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let params = &Params::from_args(PARAMS);
+    advent_of_code::solve!(1, part_one, input, params);
+    advent_of_code::solve!(2, part_two, input, params);
 }
 
 #[cfg(test)]
@@ -136,12 +104,14 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 15);
-        assert_eq!(part_one(&input), Some(26));
+        let params = Params::new().with("row", 10);
+        assert_eq!(part_one(&input, &params), Ok(26));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 15);
-        assert_eq!(part_two(&input), Some(56000011));
+        let params = Params::new().with("max_coord", 20);
+        assert_eq!(part_two(&input, &params), Ok(56000011));
     }
 }
@@ -6,151 +6,89 @@
 // and pruning the partial paths with the same open valves and relieved flow
 // at the same time.
 
-use itertools::Itertools;
-use std::{
-    cmp::Ordering,
-    collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
+use advent_of_code::{
+    error::{AocError, AocResult},
+    graph::{DistanceMatrix, Graph},
+    parse::{Cursor, ParseResult},
 };
-
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct Valve<'a> {
-    name: &'a str,
-    flow: u32,
-    neighbours: HashSet<&'a str>,
-}
-
-#[derive(PartialEq, Eq)]
-struct Node<'a> {
-    cost: u32,
-    curr: &'a str,
-}
-
-impl<'a> Ord for Node<'a> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-
-impl<'a> PartialOrd for Node<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct Path<'a> {
-    opened: BTreeSet<&'a str>,
+struct Path {
+    opened: BTreeSet<u32>,
     elapsed: u32,
     relieved: u32,
-    curr: &'a str,
-}
-
-fn load_valves(input: &str) -> HashMap<&str, Valve> {
-    let mut valves: HashMap<&str, Valve> = HashMap::new();
-    let lines = input.lines();
-    for l in lines {
-        let (valve, neighbours) = l.split_once(";").unwrap();
-        let valve = valve.strip_prefix("Valve ").unwrap();
-        let (valve, flow) = valve.split_once(" has flow rate=").unwrap();
-        let flow = flow.parse().unwrap();
-        // I changed the input files to match this prefix, for simplicity
-        let neighbours = neighbours.strip_prefix(" tunnels lead to valves ").unwrap();
-        let neighbours = neighbours.split_terminator(", ").collect();
-
-        valves.insert(
-            &valve,
-            Valve {
-                name: &valve,
-                flow: flow,
-                neighbours: neighbours,
-            },
-        );
-    }
-
-    valves
+    curr: u32,
 }
 
-fn min_cost(from: &str, to: &str, map: &HashMap<&str, Valve>) -> u32 {
-    let mut queue = BinaryHeap::new();
-    let mut visited = HashSet::new();
-
-    queue.push(Node {
-        cost: 0,
-        curr: from,
-    });
-
-    visited.insert(from);
-
-    while let Some(Node { cost, curr }) = queue.pop() {
-        if curr == to {
-            return cost;
+// Valves graph, every node holds the valve flow rate
+fn load_valves(input: &str) -> ParseResult<Graph<u32, ()>> {
+    let mut valves = Graph::new();
+    let mut cursor = Cursor::new(input);
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        cursor.tag("Valve ")?;
+        let valve = valves.add_node(cursor.word()?);
+        cursor.tag(" has flow rate=")?;
+        *valves.node_mut(valve) = cursor.integer()?;
+        // A single neighbour is written "tunnel leads to valve XX"
+        if cursor.tag("; tunnels lead to valves ").is_err() {
+            cursor.tag("; tunnel leads to valve ")?;
         }
-
-        for neighbour in map[curr].neighbours.iter() {
-            if visited.insert(neighbour) {
-                queue.push(Node {
-                    cost: cost + 1,
-                    curr: &neighbour,
-                })
-            }
+        for neighbour in cursor.separated(',', Cursor::word)? {
+            let neighbour = valves.add_node(neighbour);
+            valves.add_edge(valve, neighbour, ());
         }
+        cursor.skip_whitespace();
     }
-    u32::MAX
+    Ok(valves)
+}
+
+fn start(valves: &Graph<u32, ()>) -> AocResult<u32> {
+    valves
+        .id("AA")
+        .ok_or(AocError::assumption("there is a valve AA"))
 }
 
 // map distance from every valve (with flow > 0, or AA) to every other valve
-fn distances<'a>(map: &'a HashMap<&str, Valve>) -> HashMap<(&'a str, &'a str), u32> {
-    map.iter()
-        .filter(|(_, valve)| valve.flow > 0)
-        .map(|(&name, _)| name)
-        .tuple_combinations()
-        .fold(HashMap::new(), |mut acc, (name1, name2)| {
-            // AA <-> name1
-            acc.entry(("AA", name1))
-                .or_insert_with(|| min_cost("AA", name1, map));
-            // AA <-> name2
-            acc.entry(("AA", name2))
-                .or_insert_with(|| min_cost("AA", name2, map));
-
-            // name1 <-> name2
-            let dist = min_cost(name1, name2, map);
-            acc.insert((name1, name2), dist);
-            acc.insert((name2, name1), dist);
-
-            acc
-        })
+fn distances(valves: &Graph<u32, ()>, start: u32) -> DistanceMatrix<u32, u32> {
+    let interesting = valves
+        .ids()
+        .filter(|&id| *valves.node(id) > 0 || id == start);
+
+    DistanceMatrix::new(interesting, |&curr| {
+        valves.neighbours(curr).map(|n| (n, 1)).collect::<Vec<_>>()
+    })
 }
 
 fn compute_relieved(
     from_time: u32,
     to_time: u32,
     relieved: u32,
-    opened: &BTreeSet<&str>,
-    map: &HashMap<&str, Valve>,
+    opened: &BTreeSet<u32>,
+    valves: &Graph<u32, ()>,
 ) -> u32 {
     let time = to_time - from_time;
-    let flow: u32 = opened.iter().map(|&name| map[name].flow).sum();
+    let flow: u32 = opened.iter().map(|&id| valves.node(id)).sum();
     flow * time + relieved
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let valves = load_valves(input);
-    let distances = distances(&valves);
+pub fn part_one(input: &str) -> AocResult<u32> {
+    let valves = load_valves(input)?;
+    let start = start(&valves)?;
+    let distances = distances(&valves, start);
 
     // Exhaustive search of all possible paths (of flowing valves)
     // We don't care about the actual path, only the opened valves, the time and the total flow
     // To optimize, we don't follow possible duplicates (kept track using the Path set)
-    let flowing_valves: HashSet<_> = valves
-        .iter()
-        .filter(|(_, v)| v.flow > 0)
-        .map(|(&n, _)| n)
-        .collect();
+    let flowing_valves: HashSet<u32> = valves.ids().filter(|&id| *valves.node(id) > 0).collect();
 
     let mut visited = HashSet::new();
     let mut q = VecDeque::new();
 
     q.push_back(Path {
-        curr: "AA",
+        curr: start,
         elapsed: 0,
         relieved: 0,
         opened: BTreeSet::new(),
@@ -178,7 +116,10 @@ pub fn part_one(input: &str) -> Option<u32> {
 
         for dest in unopened {
             // If over max time, do as above -> compute total flow, check if maximum and skip
-            let cost = distances[&(curr, *dest)] + 1;
+            let Some(distance) = distances.distance(&curr, dest) else {
+                continue;
+            };
+            let cost = distance + 1;
             let new_elapsed = elapsed + cost;
             if new_elapsed >= 30 {
                 let final_relieve = compute_relieved(elapsed, 30, relieved, &opened, &valves);
@@ -188,12 +129,12 @@ pub fn part_one(input: &str) -> Option<u32> {
 
             let new_relieved = compute_relieved(elapsed, new_elapsed, relieved, &opened, &valves);
             let mut new_opened = opened.clone();
-            new_opened.insert(dest);
+            new_opened.insert(*dest);
 
             if visited.insert((new_opened.clone(), new_elapsed, new_relieved)) {
                 q.push_back(Path {
                     opened: new_opened,
-                    curr: dest,
+                    curr: *dest,
                     relieved: new_relieved,
                     elapsed: new_elapsed,
                 });
@@ -201,31 +142,28 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
     }
 
-    Some(max_relieved)
+    Ok(max_relieved)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let valves = load_valves(input);
-    let distances = distances(&valves);
+pub fn part_two(input: &str) -> AocResult<u32> {
+    let valves = load_valves(input)?;
+    let start = start(&valves)?;
+    let distances = distances(&valves, start);
 
     // Exhaustive search of all possible paths (of flowing valves) without optimizing.
     // Also accept partial paths, as they will be merged later (player + elephant)
-    let flowing_valves: HashSet<_> = valves
-        .iter()
-        .filter(|(_, v)| v.flow > 0)
-        .map(|(&n, _)| n)
-        .collect();
+    let flowing_valves: HashSet<u32> = valves.ids().filter(|&id| *valves.node(id) > 0).collect();
 
     let mut q = VecDeque::new();
 
     q.push_back(Path {
-        curr: "AA",
+        curr: start,
         elapsed: 0,
         relieved: 0,
         opened: BTreeSet::new(),
     });
 
-    let mut max_relieved_paths: HashMap<BTreeSet<&str>, u32> = HashMap::new();
+    let mut max_relieved_paths: HashMap<BTreeSet<u32>, u32> = HashMap::new();
 
     while let Some(Path {
         opened,
@@ -248,7 +186,10 @@ pub fn part_two(input: &str) -> Option<u32> {
 
         for dest in unopened {
             // If over max time, do as above -> compute total flow, check if maximum and skip
-            let cost = distances[&(curr, *dest)] + 1;
+            let Some(distance) = distances.distance(&curr, dest) else {
+                continue;
+            };
+            let cost = distance + 1;
             let new_elapsed = elapsed + cost;
             if new_elapsed >= 26 {
                 continue;
@@ -256,11 +197,11 @@ pub fn part_two(input: &str) -> Option<u32> {
 
             let new_relieved = compute_relieved(elapsed, new_elapsed, relieved, &opened, &valves);
             let mut new_opened = opened.clone();
-            new_opened.insert(dest);
+            new_opened.insert(*dest);
 
             q.push_back(Path {
                 opened: new_opened,
-                curr: dest,
+                curr: *dest,
                 relieved: new_relieved,
                 elapsed: new_elapsed,
             });
@@ -275,9 +216,9 @@ pub fn part_two(input: &str) -> Option<u32> {
         .filter(|(human, elephant)| human.0.is_disjoint(elephant.0))
         .map(|(human, elephant)| human.1 + elephant.1)
         .max()
-        .unwrap();
+        .unwrap_or(0);
 
-    Some(max_final)
+    Ok(max_final)
 }
 
 fn main() {
@@ -293,12 +234,23 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 16);
-        assert_eq!(part_one(&input), Some(1651));
+        assert_eq!(part_one(&input), Ok(1651));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 16);
-        assert_eq!(part_two(&input), Some(1707));
+        assert_eq!(part_two(&input), Ok(1707));
+    }
+
+    #[test]
+    fn test_load_valves() {
+        let input = "Valve AA has flow rate=0; tunnel leads to valve BB\nValve BB has flow rate=3; tunnels lead to valves AA, CC";
+        let valves = load_valves(input).unwrap();
+        assert_eq!(3, valves.len());
+        assert_eq!(&3, valves.node(valves.id("BB").unwrap()));
+
+        let error = load_valves("Valve AA has flow rate=x; tunnel leads to valve BB").unwrap_err();
+        assert_eq!((1, 24), (error.line, error.column));
     }
 }
@@ -1,5 +1,10 @@
-use std::collections::HashMap;
+use advent_of_code::{
+    cycle,
+    error::AocResult,
+    parse::{Cursor, ParseResult},
+};
 
+#[derive(Debug)]
 enum Jet {
     Left,
     Right,
@@ -60,16 +65,22 @@ const SHAPES: [&[Coord]; 5] = [
     ],
 ];
 
-fn parse_input(input: &str) -> Vec<Jet> {
-    input
-        .trim()
-        .chars()
-        .map(|c| match c {
+fn parse_input(input: &str) -> ParseResult<Vec<Jet>> {
+    let mut cursor = Cursor::new(input.trim_end());
+    let mut jets = vec![];
+    while let Some(c) = cursor.peek() {
+        jets.push(match c {
             '<' => Jet::Left,
             '>' => Jet::Right,
-            _ => panic!("Invalid input char {}", c),
-        })
-        .collect()
+            _ => return Err(cursor.error(format!("Expected '<' or '>', found {:?}", c))),
+        });
+        cursor.char(c)?;
+    }
+    // The jets repeat, there must be at least one
+    if jets.is_empty() {
+        return Err(cursor.error("Expected jets"));
+    }
+    Ok(jets)
 }
 
 #[derive(Default)]
@@ -80,10 +91,41 @@ struct Cave<'a> {
     jets: Vec<Jet>,
     top: usize,
     map: Vec<[bool; WIDTH]>,
+    heights: [usize; WIDTH],
     rocks_count: usize,
 }
 
 impl Cave<'_> {
+    fn new(jets: Vec<Jet>) -> Self {
+        Cave {
+            jets,
+            curr_shape: SHAPES[0],
+            ..Default::default()
+        }
+    }
+
+    fn drop_rock(&mut self) {
+        self.add_rock_on_top();
+        loop {
+            self.apply_jet();
+            if !self.move_down() {
+                break;
+            }
+        }
+        self.settle_current_rock();
+        self.rocks_count += 1;
+    }
+
+    // The next rocks only depend on the next shape, the next jet and the surface
+    // of the rocks, given by the depth of every column under the top
+    fn key(&self) -> (usize, usize, [usize; WIDTH]) {
+        (
+            self.rocks_count % SHAPES.len(),
+            self.curr_jet % self.jets.len(),
+            self.heights.map(|h| self.top - h),
+        )
+    }
+
     fn add_rock_on_top(&mut self) {
         self.curr_pos.x = 2;
         self.curr_pos.y = self.top + 3;
@@ -128,6 +170,7 @@ impl Cave<'_> {
                 self.map.push([false; WIDTH]);
             }
             self.map[y][x] = true;
+            self.heights[x] = self.heights[x].max(y + 1);
             self.top = self.top.max(y + 1);
         }
     }
@@ -145,94 +188,19 @@ impl Cave<'_> {
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut cave = Cave {
-        jets: parse_input(input),
-        curr_jet: 0,
-        curr_shape: &SHAPES[0],
-        curr_pos: Coord { x: 0, y: 0 },
-        rocks_count: 0,
-        top: 0,
-        map: Vec::new(),
-    };
-
-    while cave.rocks_count != 2022 {
-        cave.add_rock_on_top();
-        loop {
-            cave.apply_jet();
-
-            if !cave.move_down() {
-                break;
-            }
-        }
-
-        cave.settle_current_rock();
-
-        cave.rocks_count += 1;
-    }
-
-    Some(cave.top as u32)
+fn tower_height(input: &str, rocks: usize) -> ParseResult<usize> {
+    let cave = Cave::new(parse_input(input)?);
+    let height = cycle::simulate_until(cave, rocks, Cave::drop_rock, Cave::key, |c| c.top as i64);
+    Ok(height as usize)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    // Need to find a pattern to repeat the most before 1000000000000 pieces
-    // Key: (shape_idx, jet_idx)
-    // Value: (counter, rocks_count, top)
-    let mut repeats: HashMap<(usize, usize), (usize, usize, usize)> = HashMap::new();
-    let mut added_by_repeating: usize = 0;
-    let target = 1_000_000_000_000;
-
-    let mut cave = Cave {
-        jets: parse_input(input),
-        curr_jet: 0,
-        curr_shape: &SHAPES[0],
-        curr_pos: Coord { x: 0, y: 0 },
-        rocks_count: 0,
-        top: 0,
-        map: Vec::new(),
-    };
-
-    while cave.rocks_count != target {
-        // As part 1
-        cave.add_rock_on_top();
-        loop {
-            cave.apply_jet();
-            if !cave.move_down() {
-                break;
-            }
-        }
-        cave.settle_current_rock();
-
-        // Check for repetition
-        if added_by_repeating == 0 {
-            let key = (
-                cave.rocks_count % SHAPES.len(), // shape idx
-                cave.curr_jet % cave.jets.len(), // jet idx
-            );
-            // Already seen this pattern 2 times, means if repeats forever
-            if let Some((2, old_rocks_count, old_top)) = repeats.get(&key) {
-                let delta_top = cave.top - old_top;
-                let delta_count = cave.rocks_count - old_rocks_count;
-                let repeats_to_do = (target - cave.rocks_count) / delta_count;
-                added_by_repeating += repeats_to_do * delta_top;
-                cave.rocks_count += repeats_to_do * delta_count;
-            }
-            repeats
-                .entry(key)
-                .and_modify(|(count, old_rocks_count, old_top)| {
-                    *count += 1;
-                    *old_rocks_count = cave.rocks_count;
-                    *old_top = cave.top;
-                })
-                .or_insert((1, cave.rocks_count, cave.top));
-        }
-
-        cave.rocks_count += 1;
-    }
-
-    cave.top += added_by_repeating;
+pub fn part_one(input: &str) -> AocResult<u32> {
+    Ok(tower_height(input, 2022)? as u32)
+}
 
-    Some(cave.top as u64)
+pub fn part_two(input: &str) -> AocResult<u64> {
+    // The rocks fall in a repeating pattern, skip ahead once found
+    Ok(tower_height(input, 1_000_000_000_000)? as u64)
 }
 
 fn main() {
@@ -248,12 +216,19 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 17);
-        assert_eq!(part_one(&input), Some(3068));
+        assert_eq!(part_one(&input), Ok(3068));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 17);
-        assert_eq!(part_two(&input), Some(1514285714288));
+        assert_eq!(part_two(&input), Ok(1514285714288));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = parse_input("<<>x>\n").unwrap_err();
+        assert_eq!((1, 4), (error.line, error.column));
+        assert!(parse_input("\n").is_err());
     }
 }
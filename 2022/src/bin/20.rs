@@ -1,78 +1,68 @@
-fn parse(input: &str) -> Vec<i64> {
-    input.lines().map(|l| l.parse::<i64>().unwrap()).collect()
+use advent_of_code::{
+    circular::CircularList,
+    error::{AocError, AocResult},
+    parse::{Cursor, ParseResult},
+};
+
+// One number per line
+fn parse(input: &str) -> ParseResult<Vec<i64>> {
+    let mut cursor = Cursor::new(input);
+    let mut values = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        values.push(cursor.integer()?);
+        let end = cursor;
+        if !cursor.line().trim().is_empty() {
+            return Err(end.error("Expected a single number on the line"));
+        }
+        cursor.skip_whitespace();
+    }
+    Ok(values)
 }
 
 struct MixingList {
-    // Original values
-    values: Vec<i64>,
-    // indices[i] => index of the value in the mixed array at position i (to mixed[i] = values[indices[i]])
-    indices: Vec<i64>,
-    key: i64,
+    // Values times the key, their handles in the list are their original indices
+    list: CircularList<i64>,
     rounds: usize,
 }
 
 impl MixingList {
+    fn new(values: &[i64], key: i64, rounds: usize) -> Self {
+        Self {
+            list: values.iter().map(|value| value * key).collect(),
+            rounds,
+        }
+    }
+
     fn mix(&mut self) {
         for _ in 0..self.rounds {
-            for i in 0..self.values.len() {
-                let val = self.values[i] * self.key;
-
-                // Find position of the value at position in the mixed array
-                let idx = self.indices.iter().position(|&x| x == i as i64).unwrap();
-                let max_idx = self.indices.len() as i64 - 1;
-
-                // New position in which to put the value
-                let new_idx = (((idx as i64) + val).rem_euclid(max_idx)) as usize;
-
-                self.indices.remove(idx);
-                self.indices.insert(new_idx, i as i64);
+            for i in 0..self.list.len() {
+                let value = *self.list.value(i);
+                self.list.shift(i, value);
             }
         }
     }
 
-    fn solve(&mut self) -> i64 {
+    fn solve(&mut self) -> AocResult<i64> {
         self.mix();
 
-        let zero_pos = self.values.iter().position(|&n| n == 0).unwrap();
-        let idx = self
-            .indices
-            .iter()
-            .position(|&n| n == zero_pos as i64)
-            .unwrap();
-        return [1000, 2000, 3000]
+        let zero = (0..self.list.len())
+            .find(|&i| *self.list.value(i) == 0)
+            .ok_or_else(|| AocError::assumption("the list contains 0"))?;
+        let idx = self.list.position(zero);
+        Ok([1000, 2000, 3000]
             .iter()
-            .map(|x| {
-                let index = self.indices[(idx + x) as usize % self.indices.len()] as usize;
-                self.values[index] * self.key
-            })
-            .sum();
+            .map(|x| self.list[(idx + x) % self.list.len()])
+            .sum())
     }
 }
 
-pub fn part_one(input: &str) -> Option<i64> {
-    let values = parse(input);
-    let indices = (0..(values.len() as i64)).collect();
-    let mut list = MixingList {
-        values,
-        indices,
-        key: 1,
-        rounds: 1,
-    };
-
-    Some(list.solve())
+pub fn part_one(input: &str) -> AocResult<i64> {
+    MixingList::new(&parse(input)?, 1, 1).solve()
 }
 
-pub fn part_two(input: &str) -> Option<i64> {
-    let values = parse(input);
-    let indices = (0..(values.len() as i64)).collect();
-    let mut list = MixingList {
-        values,
-        indices,
-        key: 811589153,
-        rounds: 10,
-    };
-
-    Some(list.solve())
+pub fn part_two(input: &str) -> AocResult<i64> {
+    MixingList::new(&parse(input)?, 811589153, 10).solve()
 }
 
 fn main() {
@@ -88,12 +78,20 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 20);
-        assert_eq!(part_one(&input), Some(3));
+        assert_eq!(part_one(&input), Ok(3));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 20);
-        assert_eq!(part_two(&input), Some(1623178306));
+        assert_eq!(part_two(&input), Ok(1623178306));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(Ok(vec![1, -2, 0]), parse("1\r\n-2\r\n0\r\n"));
+        let error = parse("1\n2 3\n").unwrap_err();
+        assert_eq!((2, 2), (error.line, error.column));
+        assert!(matches!(part_one("1\n2\n"), Err(AocError::Assumption(_))));
     }
 }
@@ -0,0 +1,322 @@
+//! Circular sequence with fast positional access, for puzzles moving elements
+//! around with wraparound.
+
+use std::{cmp::Ordering, ops::Index};
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    // `None` once removed, the slot is then reused
+    value: Option<T>,
+    priority: u64,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    parent: Option<usize>,
+}
+
+/// Sequence stored as an implicit treap: a binary tree ordered by position (every
+/// node knows the size of its subtree) and balanced by random priorities. Finding,
+/// inserting, removing, moving and rotating are all O(log n).
+///
+/// Every element gets a handle when added, which stays the same wherever it moves,
+/// to find its position later.
+#[derive(Debug, Clone)]
+pub struct CircularList<T> {
+    nodes: Vec<Node<T>>,
+    root: Option<usize>,
+    free: Vec<usize>,
+    seed: u64,
+}
+
+impl<T> Default for CircularList<T> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            root: None,
+            free: vec![],
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Adds a value at the end, giving its handle.
+    pub fn push(&mut self, value: T) -> usize {
+        self.insert(self.len(), value)
+    }
+
+    /// Adds a value so it ends up at `position`, giving its handle.
+    pub fn insert(&mut self, position: usize, value: T) -> usize {
+        assert!(position <= self.len(), "Position out of bounds");
+        let node = Node {
+            value: Some(value),
+            priority: self.next_priority(),
+            size: 1,
+            left: None,
+            right: None,
+            parent: None,
+        };
+        let handle = match self.free.pop() {
+            Some(handle) => {
+                self.nodes[handle] = node;
+                handle
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        self.attach(handle, position);
+        handle
+    }
+
+    /// Removes the value at `position`. Its handle can be given to a new value.
+    pub fn remove(&mut self, position: usize) -> T {
+        let handle = self.handle_at(position);
+        self.detach(position);
+        self.free.push(handle);
+        self.nodes[handle].value.take().unwrap()
+    }
+
+    /// Current position of the value with this handle.
+    pub fn position(&self, handle: usize) -> usize {
+        let mut position = self.size(self.nodes[handle].left);
+        let mut node = handle;
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].right == Some(node) {
+                position += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+        position
+    }
+
+    /// Handle of the value at `position`.
+    pub fn handle_at(&self, mut position: usize) -> usize {
+        assert!(position < self.len(), "Position out of bounds");
+        let mut node = self.root.unwrap();
+        loop {
+            let left = self.size(self.nodes[node].left);
+            match position.cmp(&left) {
+                Ordering::Less => node = self.nodes[node].left.unwrap(),
+                Ordering::Equal => return node,
+                Ordering::Greater => {
+                    position -= left + 1;
+                    node = self.nodes[node].right.unwrap();
+                }
+            }
+        }
+    }
+
+    /// Value with this handle.
+    pub fn value(&self, handle: usize) -> &T {
+        self.nodes[handle].value.as_ref().unwrap()
+    }
+
+    /// Takes the value with this handle out and puts it back `by` positions
+    /// further (backwards when negative), wrapping around the other values. Gives
+    /// its new position.
+    pub fn shift(&mut self, handle: usize, by: i64) -> usize {
+        let others = self.len() as i64 - 1;
+        if others == 0 {
+            return 0;
+        }
+        let position = self.position(handle);
+        self.detach(position);
+        let target = (position as i64 + by).rem_euclid(others) as usize;
+        self.attach(handle, target);
+        target
+    }
+
+    /// Moves the first `by` values to the end (or the last ones to the front when
+    /// negative).
+    pub fn rotate(&mut self, by: i64) {
+        if self.is_empty() {
+            return;
+        }
+        let by = by.rem_euclid(self.len() as i64) as usize;
+        let (front, back) = self.split(self.root, by);
+        self.root = self.merge(back, front);
+        self.set_parent(self.root, None);
+    }
+
+    /// Values in order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len()).map(|position| &self[position])
+    }
+
+    // Xorshift, the priorities only need to look random
+    fn next_priority(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    fn set_parent(&mut self, node: Option<usize>, parent: Option<usize>) {
+        if let Some(node) = node {
+            self.nodes[node].parent = parent;
+        }
+    }
+
+    // Recomputes the size of the node, and links its children back to it
+    fn update(&mut self, node: usize) {
+        let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+        self.set_parent(left, Some(node));
+        self.set_parent(right, Some(node));
+    }
+
+    // First `count` values of the tree, and the others
+    fn split(&mut self, node: Option<usize>, count: usize) -> (Option<usize>, Option<usize>) {
+        let Some(node) = node else {
+            return (None, None);
+        };
+
+        let left = self.size(self.nodes[node].left);
+        if count <= left {
+            let (first, rest) = self.split(self.nodes[node].left, count);
+            self.nodes[node].left = rest;
+            self.update(node);
+            self.set_parent(first, None);
+            (first, Some(node))
+        } else {
+            let (first, rest) = self.split(self.nodes[node].right, count - left - 1);
+            self.nodes[node].right = first;
+            self.update(node);
+            self.set_parent(rest, None);
+            (Some(node), rest)
+        }
+    }
+
+    // Tree with the values of `a` then the ones of `b`
+    fn merge(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        match (a, b) {
+            (None, other) | (other, None) => other,
+            (Some(a), Some(b)) if self.nodes[a].priority > self.nodes[b].priority => {
+                let right = self.nodes[a].right;
+                self.nodes[a].right = self.merge(right, Some(b));
+                self.update(a);
+                Some(a)
+            }
+            (Some(a), Some(b)) => {
+                let left = self.nodes[b].left;
+                self.nodes[b].left = self.merge(Some(a), left);
+                self.update(b);
+                Some(b)
+            }
+        }
+    }
+
+    // Puts a lone node back in the tree at `position`
+    fn attach(&mut self, node: usize, position: usize) {
+        let (before, after) = self.split(self.root, position);
+        let front = self.merge(before, Some(node));
+        self.root = self.merge(front, after);
+        self.set_parent(self.root, None);
+    }
+
+    // Takes the node at `position` out of the tree, keeping its value
+    fn detach(&mut self, position: usize) {
+        let (before, rest) = self.split(self.root, position);
+        let (node, after) = self.split(rest, 1);
+        self.root = self.merge(before, after);
+        self.set_parent(self.root, None);
+
+        let node = &mut self.nodes[node.unwrap()];
+        (node.left, node.right, node.parent, node.size) = (None, None, None, 1);
+    }
+}
+
+impl<T> Index<usize> for CircularList<T> {
+    type Output = T;
+
+    fn index(&self, position: usize) -> &T {
+        self.value(self.handle_at(position))
+    }
+}
+
+/// Handles are given in order from 0.
+impl<T> FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for value in iter {
+            list.push(value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(list: &CircularList<i64>) -> Vec<i64> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut list: CircularList<i64> = (0..5).collect();
+        assert_eq!(5, list.len());
+        assert_eq!(3, list.position(3));
+
+        let handle = list.insert(2, 10);
+        assert_eq!(vec![0, 1, 10, 2, 3, 4], values(&list));
+        assert_eq!(2, list.position(handle));
+        assert_eq!(4, list.position(3));
+
+        assert_eq!(0, list.remove(0));
+        assert_eq!(4, list.remove(4));
+        assert_eq!(vec![1, 10, 2, 3], values(&list));
+        assert_eq!(handle, list.handle_at(1));
+        assert_eq!(&3, list.value(3));
+    }
+
+    #[test]
+    fn test_shift_rotate() {
+        // The mixing example of 2022 day 20
+        let mut list: CircularList<i64> = [1, 2, -3, 3, -2, 0, 4].into_iter().collect();
+        for handle in 0..list.len() {
+            let by = *list.value(handle);
+            list.shift(handle, by);
+        }
+        let zero = list.position(5);
+        list.rotate(zero as i64);
+        assert_eq!(vec![0, 3, -2, 1, 2, -3, 4], values(&list));
+
+        list.rotate(-2);
+        assert_eq!(vec![-3, 4, 0, 3, -2, 1, 2], values(&list));
+        assert_eq!(2, list.shift(2, -10));
+    }
+
+    #[test]
+    fn test_large() {
+        // Moving every value to the front in reverse order
+        let n = 50_000;
+        let mut list: CircularList<i64> = (0..n).collect();
+        for handle in 0..n as usize {
+            let position = list.position(handle);
+            list.shift(handle, -(position as i64));
+        }
+        assert_eq!(n - 1, list[0]);
+        assert_eq!(0, list[n as usize - 1]);
+        assert_eq!(n as usize / 2, list.position(n as usize / 2 - 1));
+    }
+}
@@ -0,0 +1,373 @@
+//! Named arithmetic expressions sharing their sub-expressions, evaluated exactly.
+
+use num::{BigInt, BigRational, Zero};
+
+use crate::{
+    error::{AocError, AocResult},
+    graph::Interner,
+    parse::{Cursor, ParseResult},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Sub),
+            '*' => Some(Operator::Mul),
+            '/' => Some(Operator::Div),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    /// Used by another expression but never defined.
+    Undefined,
+    Number(BigRational),
+    /// Operator with the ids of its left and right operands.
+    Operation(Operator, u32, u32),
+}
+
+/// `a * x + b`, an expression depending at most linearly on one unknown `x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linear {
+    pub a: BigRational,
+    pub b: BigRational,
+}
+
+impl Linear {
+    fn constant(b: BigRational) -> Self {
+        Self {
+            a: BigRational::zero(),
+            b,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.a.is_zero()
+    }
+
+    fn scale(&self, factor: &BigRational) -> Self {
+        Self {
+            a: &self.a * factor,
+            b: &self.b * factor,
+        }
+    }
+
+    // `None` when the result is not linear
+    fn apply(&self, operator: Operator, other: &Linear) -> Option<Self> {
+        match operator {
+            Operator::Add => Some(Self {
+                a: &self.a + &other.a,
+                b: &self.b + &other.b,
+            }),
+            Operator::Sub => Some(Self {
+                a: &self.a - &other.a,
+                b: &self.b - &other.b,
+            }),
+            Operator::Mul if self.is_constant() => Some(other.scale(&self.b)),
+            Operator::Mul if other.is_constant() => Some(self.scale(&other.b)),
+            Operator::Div if other.is_constant() && !other.b.is_zero() => {
+                Some(self.scale(&other.b.recip()))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Evaluation state of an expression
+#[derive(Clone)]
+enum Memo {
+    Todo,
+    InProgress,
+    Done(Linear),
+}
+
+/// Expressions defined by name, like `root: left + right`. Names are interned to
+/// ids and every expression exists once, however many others use it, so they form
+/// a DAG which is evaluated once per node.
+#[derive(Debug, Clone, Default)]
+pub struct Expressions {
+    names: Interner,
+    expressions: Vec<Expression>,
+}
+
+impl Expressions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One `name: number` or `name: left op right` per line.
+    pub fn parse(input: &str) -> ParseResult<Self> {
+        let mut expressions = Self::new();
+        let mut cursor = Cursor::new(input);
+        cursor.skip_whitespace();
+        while !cursor.is_empty() {
+            let name = cursor.word()?;
+            cursor.tag(":")?;
+            cursor.skip_spaces();
+
+            if matches!(cursor.peek(), Some(c) if c == '-' || c.is_ascii_digit()) {
+                let value: i64 = cursor.integer()?;
+                expressions.number(name, value);
+            } else {
+                let left = cursor.word()?;
+                cursor.skip_spaces();
+                let start = cursor;
+                let operator = cursor.take_while(|c| !c.is_whitespace());
+                let operator = match (
+                    operator.len(),
+                    operator.chars().find_map(Operator::from_char),
+                ) {
+                    (1, Some(operator)) => operator,
+                    _ => return Err(start.error(format!("Unknown operator {:?}", operator))),
+                };
+                cursor.skip_spaces();
+                let right = cursor.word()?;
+                expressions.operation(name, operator, left, right);
+            }
+            cursor.skip_whitespace();
+        }
+        Ok(expressions)
+    }
+
+    // Id of the name, declaring it undefined if it is new
+    fn intern(&mut self, name: &str) -> u32 {
+        let id = self.names.intern(name);
+        if id as usize == self.expressions.len() {
+            self.expressions.push(Expression::Undefined);
+        }
+        id
+    }
+
+    pub fn number(&mut self, name: &str, value: impl Into<BigInt>) {
+        let id = self.intern(name);
+        self.expressions[id as usize] = Expression::Number(BigRational::from_integer(value.into()));
+    }
+
+    pub fn operation(&mut self, name: &str, operator: Operator, left: &str, right: &str) {
+        let id = self.intern(name);
+        let operation = Expression::Operation(operator, self.intern(left), self.intern(right));
+        self.expressions[id as usize] = operation;
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Expression> {
+        self.names.id(name).map(|id| &self.expressions[id as usize])
+    }
+
+    /// Names of the operands of an operation.
+    pub fn operands(&self, name: &str) -> Option<(&str, &str)> {
+        match self.get(name)? {
+            Expression::Operation(_, left, right) => {
+                Some((self.names.name(*left), self.names.name(*right)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Exact value of the expression.
+    pub fn evaluate(&self, name: &str) -> AocResult<BigRational> {
+        Ok(self.linearise(name, None)?.b)
+    }
+
+    /// The expression as `a * unknown + b`, whatever the unknown is defined as
+    /// (or with `a = 0` without unknown). Fails when the expression does not depend
+    /// linearly on the unknown, like `x * x` or `1 / x`.
+    pub fn linearise(&self, name: &str, unknown: Option<&str>) -> AocResult<Linear> {
+        let id = self.id(name)?;
+        let unknown = unknown.and_then(|unknown| self.names.id(unknown));
+        let mut memo = vec![Memo::Todo; self.expressions.len()];
+        self.linear(id, unknown, &mut memo)
+    }
+
+    /// Value of `unknown` for `lhs = rhs`.
+    pub fn solve(&self, lhs: &str, rhs: &str, unknown: &str) -> AocResult<BigRational> {
+        let lhs = self.linearise(lhs, Some(unknown))?;
+        let rhs = self.linearise(rhs, Some(unknown))?;
+
+        // (lhs.a - rhs.a) * x = rhs.b - lhs.b
+        let a = lhs.a - rhs.a;
+        let b = rhs.b - lhs.b;
+        match (a.is_zero(), b.is_zero()) {
+            (false, _) => Ok(b / a),
+            (true, true) => Err(AocError::assumption(format!(
+                "a single value of {} is a solution",
+                unknown
+            ))),
+            (true, false) => Err(AocError::NoSolution),
+        }
+    }
+
+    fn id(&self, name: &str) -> AocResult<u32> {
+        self.names
+            .id(name)
+            .ok_or_else(|| AocError::assumption(format!("{} is defined", name)))
+    }
+
+    fn linear(&self, id: u32, unknown: Option<u32>, memo: &mut Vec<Memo>) -> AocResult<Linear> {
+        match &memo[id as usize] {
+            Memo::Done(linear) => return Ok(linear.clone()),
+            Memo::InProgress => {
+                return Err(AocError::assumption(format!(
+                    "{} does not depend on itself",
+                    self.names.name(id)
+                )))
+            }
+            Memo::Todo => memo[id as usize] = Memo::InProgress,
+        }
+
+        let name = self.names.name(id);
+        let linear = match &self.expressions[id as usize] {
+            _ if Some(id) == unknown => Linear {
+                a: BigRational::from_integer(1.into()),
+                b: BigRational::zero(),
+            },
+            Expression::Undefined => {
+                return Err(AocError::assumption(format!("{} is defined", name)))
+            }
+            Expression::Number(value) => Linear::constant(value.clone()),
+            Expression::Operation(operator, left, right) => {
+                let left = self.linear(*left, unknown, memo)?;
+                let right = self.linear(*right, unknown, memo)?;
+                match left.apply(*operator, &right) {
+                    Some(linear) => linear,
+                    None if *operator == Operator::Div
+                        && right.is_constant()
+                        && right.b.is_zero() =>
+                    {
+                        return Err(AocError::assumption(format!(
+                            "{} does not divide by 0",
+                            name
+                        )))
+                    }
+                    None => {
+                        let unknown = self.names.name(unknown.unwrap());
+                        return Err(AocError::assumption(format!(
+                            "{} is linear in {}",
+                            name, unknown
+                        )));
+                    }
+                }
+            }
+        };
+
+        memo[id as usize] = Memo::Done(linear.clone());
+        Ok(linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rational(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(numerator.into(), denominator.into())
+    }
+
+    #[test]
+    fn test_parse() {
+        let expressions = Expressions::parse("root: a / b\na: 7\nb: -2\n").unwrap();
+        assert_eq!(Some(("a", "b")), expressions.operands("root"));
+        assert_eq!(Ok(rational(-7, 2)), expressions.evaluate("root"));
+
+        let error = Expressions::parse("root: a % b").unwrap_err();
+        assert_eq!((1, 9), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_evaluate_shared() {
+        // 2^100 with each level using the previous one twice
+        let mut expressions = Expressions::new();
+        expressions.number("x0", 1);
+        for i in 1..=100 {
+            let previous = format!("x{}", i - 1);
+            expressions.operation(&format!("x{}", i), Operator::Add, &previous, &previous);
+        }
+        let expected = BigRational::from_integer(BigInt::from(2).pow(100));
+        assert_eq!(Ok(expected), expressions.evaluate("x100"));
+    }
+
+    #[test]
+    fn test_solve() {
+        // (x - 3) / 4 * 6 = 2 * x
+        let expressions = Expressions::parse(
+            &[
+                "lhs: d * six",
+                "d: s / four",
+                "s: x - three",
+                "rhs: two * x",
+                "x: 0",
+                "three: 3",
+                "four: 4",
+                "six: 6",
+                "two: 2",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        assert_eq!(Ok(rational(-9, 1)), expressions.solve("lhs", "rhs", "x"));
+        assert_eq!(
+            Ok(Linear {
+                a: rational(3, 2),
+                b: rational(-9, 2)
+            }),
+            expressions.linearise("lhs", Some("x"))
+        );
+        assert_eq!(
+            Err(AocError::NoSolution),
+            expressions.solve("three", "four", "x")
+        );
+        assert_eq!(
+            Err(AocError::assumption("a single value of x is a solution")),
+            expressions.solve("x", "x", "x")
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let expressions = Expressions::parse(
+            &[
+                "square: x * x",
+                "inverse: one / x",
+                "zero: one / z",
+                "loop: one + loop",
+                "missing: one + y",
+                "x: 1",
+                "one: 1",
+                "z: 0",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        assert_eq!(Ok(rational(1, 1)), expressions.evaluate("square"));
+        assert_eq!(
+            Err(AocError::assumption("square is linear in x")),
+            expressions.linearise("square", Some("x"))
+        );
+        assert_eq!(
+            Err(AocError::assumption("inverse is linear in x")),
+            expressions.solve("inverse", "one", "x")
+        );
+        assert_eq!(
+            Err(AocError::assumption("zero does not divide by 0")),
+            expressions.evaluate("zero")
+        );
+        assert_eq!(
+            Err(AocError::assumption("loop does not depend on itself")),
+            expressions.evaluate("loop")
+        );
+        assert_eq!(
+            Err(AocError::assumption("y is defined")),
+            expressions.evaluate("missing")
+        );
+    }
+}
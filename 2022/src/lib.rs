@@ -6,32 +6,37 @@
 use std::env;
 use std::fs;
 
+pub mod circular;
+pub mod expression;
 pub mod helpers;
 
+pub use aoc_common::{cycle, error, graph, interval, math, params, parse, search, vm};
+
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
 #[macro_export]
 macro_rules! solve {
-    ($part:expr, $solver:ident, $input:expr) => {{
+    ($part:expr, $solver:expr, $input:expr) => {{
+        use advent_of_code::error::Answer;
         use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
         use std::fmt::Display;
         use std::time::Instant;
 
-        fn print_result<T: Display>(func: impl FnOnce(&str) -> Option<T>, input: &str) {
+        fn print_result<T: Display, A: Answer<T>>(func: impl FnOnce(&str) -> A, input: &str) {
             let timer = Instant::now();
-            let result = func(input);
+            let result = func(input).into_result();
             let elapsed = timer.elapsed();
             match result {
-                Some(result) => {
+                Ok(result) => {
                     println!(
                         "{} {}(elapsed: {:.2?}){}",
                         result, ANSI_ITALIC, elapsed, ANSI_RESET
                     );
                 }
-                None => {
-                    println!("not solved.")
+                Err(e) => {
+                    println!("failed: {}", e)
                 }
             }
         }
@@ -39,6 +44,16 @@ macro_rules! solve {
         println!("🎄 {}Part {}{} 🎄", ANSI_BOLD, $part, ANSI_RESET);
         print_result($solver, $input);
     }};
+    // Parameters as read by `Params::from_args`, an invalid one fails the part
+    ($part:expr, $solver:ident, $input:expr, $params:expr) => {{
+        use advent_of_code::params::{ParamError, Params};
+        let params: &Result<Params, ParamError> = $params;
+        advent_of_code::solve!(
+            $part,
+            |input: &str| $solver(input, &params.clone()?),
+            $input
+        );
+    }};
 }
 
 pub fn read_file(folder: &str, day: u8) -> String {
@@ -2,16 +2,30 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
-use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use advent_of_code::{error::AocError, params::Params, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use std::env;
+use std::path::Path;
 use std::process::Command;
 
 fn main() {
+    // Puzzle parameters are given per day as `--param 15.row=10`, each day
+    // only gets its own ones and rejects the keys it does not know
+    let params = match Params::by_day(env::args().skip(1)) {
+        Ok(params) => params,
+        Err(e) => {
+            println!("{}", AocError::from(e));
+            return;
+        }
+    };
+
     let total: f64 = (1..=25)
         .map(|day| {
+            let day_params = params.get(&day).map(Params::to_args).unwrap_or_default();
             let day = format!("{:02}", day);
 
             let cmd = Command::new("cargo")
-                .args(["run", "--release", "--bin", &day])
+                .args(["run", "--release", "--bin", &day, "--"])
+                .args(&day_params)
                 .output()
                 .unwrap();
 
@@ -22,6 +36,17 @@ fn main() {
             let output = String::from_utf8(cmd.stdout).unwrap();
             let is_empty = output.is_empty();
 
+            // Report the reason of a crash, with whatever the day printed before it
+            let exists = Path::new(&format!("src/bin/{}.rs", day)).exists();
+            if exists && !cmd.status.success() {
+                let stderr = String::from_utf8_lossy(&cmd.stderr);
+                let mut lines = stderr.lines().skip_while(|l| !l.contains("panicked at"));
+                let reason = lines.nth(1).unwrap_or("unknown error");
+                println!("{}", output.trim());
+                println!("Crashed: {}", reason);
+                return 0_f64;
+            }
+
             println!(
                 "{}",
                 if is_empty {
@@ -1,159 +1,76 @@
-use advent_of_code::helpers::get_numbers;
-use std::cmp::{min, max};
-
-struct Converter {
-    pub map: Vec<(ExtRange, ExtRange)>,
-}
-
-impl Converter {
-    pub fn new(str: &str) -> Self {
-        let map = str
-            .lines()
-            .skip(1)
-            .map(|l| get_numbers(&l))
-            .map(|nums| (ExtRange::new(nums[1], nums[2]), ExtRange::new(nums[0], nums[2])))
-            .collect();
-
-        Converter { map }
-    }
-
-    pub fn convert(&self, source: usize) -> usize {
-        let find_result = self.map.iter().find(|(range, _)| range.contains(source));
-        if let Some((range, dest)) = find_result {
-            return dest.start + (source - range.start);
-        }
-        source
-    }
-
-    pub fn convert_range(&self, source: ExtRange) -> Vec<ExtRange> {
-        let mut output : Vec<ExtRange> = Vec::new();
-        let mut intersections : Vec<ExtRange> = Vec::new();
-
-        for (src, dest) in &self.map {
-            if let Some(intersection) = src.intersection(&source) {
-                output.push(ExtRange {
-                    start: dest.start + (intersection.start - src.start),
-                    end: dest.start + (intersection.end - src.start),
-                });
-                intersections.push(intersection);
-            }
-        }
-
-        output.extend(source.remove_ranges(&intersections));
-        output
-    }
-}
-
-#[derive(Eq, Debug, PartialEq, PartialOrd, Clone)]
-struct ExtRange {
-    pub start: usize, 
-    pub end: usize,
-}
-
-impl ExtRange {
-    pub fn new(start:usize, size: usize) -> Self {
-        ExtRange {
-            start,
-            end: start+size,
-        }
-    }
-
-    pub fn intersection(&self, other: &ExtRange) -> Option<ExtRange> {
-        let start = max(self.start, other.start);
-        let end = min(self.end, other.end);
-        if end > start {
-            Some(ExtRange {start, end})
-        } else {
-            None
-        }
-    }
-
-    pub fn contains(&self, value: usize) -> bool {
-        value >= self.start && value < self.end
-    }
-
-    pub fn remove_ranges(&self, to_remove: &Vec<ExtRange>) -> Vec<ExtRange> {
-        let mut to_remove_sorted = to_remove.to_vec();
-        to_remove_sorted.sort_by(|a, b| a.cmp(&b));
-
-        let mut output : Vec<ExtRange> = Vec::new();
-        let mut pos = self.start;
-        for r in to_remove_sorted.iter() {
-            if r.start > pos {
-                output.push(ExtRange {start: pos, end: r.start});
-                pos = r.end;
-                continue;
+use advent_of_code::{
+    error::{AocError, AocResult},
+    interval::{Interval, IntervalSet, RangeMap},
+    parse::{self, Cursor, ParseResult},
+};
+
+// The lines of one map after its header, up to a blank line
+fn parse_converter(cursor: &mut Cursor) -> ParseResult<RangeMap<usize>> {
+    let mut converter = RangeMap::new();
+    cursor.line();
+    while !matches!(cursor.peek(), None | Some('\n' | '\r')) {
+        let start = *cursor;
+        match parse::integers(cursor.line()).map_err(|e| e.within(&start))?[..] {
+            [destination, source, len] => {
+                converter.insert(Interval::with_len(source, len), destination)
             }
-            if r.end > pos {
-                pos = r.end;
-            }
-        }
-        if pos < self.end {
-            output.push(ExtRange {start: pos, end: self.end});
+            _ => return Err(start.error("Expected 3 integers")),
         }
-
-        output
     }
+    Ok(converter)
 }
 
-impl Ord for ExtRange {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self == other {
-            return std::cmp::Ordering::Equal;
-        }
-
-        if self.end < other.end  {
-            return std::cmp::Ordering::Less;
-        } else {
-            return std::cmp::Ordering::Greater;
-        }
+// The seeds, then the converters in order
+fn parse_input(input: &str) -> ParseResult<(Vec<usize>, Vec<RangeMap<usize>>)> {
+    let mut cursor = Cursor::new(input);
+    let seeds = parse::integers(cursor.line())?;
+    let mut converters = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        converters.push(parse_converter(&mut cursor)?);
+        cursor.skip_whitespace();
     }
+    Ok((seeds, converters))
 }
 
-fn find_min_convert(seeds: Vec<usize>, converters: &Vec<Converter>) -> u32 {
+fn find_min_convert(seeds: Vec<usize>, converters: &Vec<RangeMap<usize>>) -> Option<u32> {
     let min_val = seeds
         .iter()
         .map(|seed| {
-            let mut value = *seed;
-            for converter in converters {
-                value = converter.convert(value);
-            }
-            value
+            converters
+                .iter()
+                .fold(*seed, |value, converter| converter.map(value))
         })
-        .min()
-        .unwrap();
+        .min()?;
 
-    min_val as u32
+    Some(min_val as u32)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut split = input.split("\n\n");
-
-    let seeds = get_numbers(split.next().unwrap());
-    let converters: Vec<Converter> = split.map(|s| Converter::new(s)).collect();
+pub fn part_one(input: &str) -> AocResult<u32> {
+    let (seeds, converters) = parse_input(input)?;
 
-    Some(find_min_convert(seeds, &converters))
+    find_min_convert(seeds, &converters).ok_or(AocError::NoSolution)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut split = input.split("\n\n");
+pub fn part_two(input: &str) -> AocResult<u32> {
+    let (seeds, converters) = parse_input(input)?;
+    if seeds.len() % 2 != 0 {
+        return Err(AocError::assumption("the seeds come in pairs"));
+    }
 
-    let seeds : Vec<ExtRange> = get_numbers(split.next().unwrap())
+    let seeds: IntervalSet<usize> = seeds
         .chunks(2)
-        .map(|n| ExtRange::new(n[0], n[1]))
+        .map(|n| Interval::with_len(n[0], n[1]))
         .collect();
-        
-    let converters: Vec<Converter> = split.map(|s| Converter::new(s)).collect();
 
-    let mut ranges = seeds.to_vec();
-    for converter in &converters {
-        ranges = ranges.iter().flat_map(|range| converter.convert_range(range.clone())).collect();
-    }
+    let ranges = converters
+        .iter()
+        .fold(seeds, |ranges, converter| converter.map_set(&ranges));
 
-    Some(ranges.iter().map(|r| r.start).min().unwrap() as u32)
+    let lowest = ranges.iter().next().ok_or(AocError::NoSolution)?.start;
+    Ok(lowest as u32)
 }
 
-
 fn main() {
     let input = &advent_of_code::read_file("inputs", 5);
     advent_of_code::solve!(1, part_one, input);
@@ -166,30 +83,43 @@ mod tests {
 
     #[test]
     fn test_range_intersection() {
-        let r = ExtRange{start: 10, end: 20};
-        let a = ExtRange {start: 7, end: 12};
-        let b = ExtRange {start: 20, end: 23};
+        let r = Interval::new(10, 20);
+        let a = Interval::new(7, 12);
+        let b = Interval::new(20, 23);
 
-        assert_eq!(Some(ExtRange{start: 10, end: 12}), r.intersection(&a));
+        assert_eq!(Some(Interval::new(10, 12)), r.intersection(&a));
         assert_eq!(None, r.intersection(&b));
     }
 
     #[test]
     fn test_range_removal() {
-        let r = ExtRange{start: 5, end: 20};
-        let ranges = vec![ExtRange{start: 2, end: 7}, ExtRange{start: 10, end: 15}];
-        assert_eq!(r.remove_ranges(&ranges), vec![ExtRange{start: 7, end: 10}, ExtRange{start: 15, end: 20}]);
+        let r = IntervalSet::from_iter([Interval::new(5, 20)]);
+        let ranges = IntervalSet::from_iter([Interval::new(2, 7), Interval::new(10, 15)]);
+        assert_eq!(
+            r.difference(&ranges).iter().copied().collect::<Vec<_>>(),
+            vec![Interval::new(7, 10), Interval::new(15, 20)]
+        );
     }
 
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 5);
-        assert_eq!(part_one(&input), Some(35));
+        assert_eq!(part_one(&input), Ok(35));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 5);
-        assert_eq!(part_two(&input), Some(46));
+        assert_eq!(part_two(&input), Ok(46));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = parse_input("seeds: 79 -14").unwrap_err();
+        assert_eq!((1, 11), (error.line, error.column));
+        let error = parse_input("seeds: 79 14\n\nseed-to-soil map:\n50 98").unwrap_err();
+        assert_eq!((4, 1), (error.line, error.column));
+        let error = parse_input("seeds: 79 14\n\nseed-to-soil map:\n50 98 -2").unwrap_err();
+        assert_eq!((4, 7), (error.line, error.column));
     }
 }
@@ -1,19 +1,60 @@
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut lines = input.lines();
-    let times = advent_of_code::helpers::get_numbers(lines.next().unwrap());
-    let distances = advent_of_code::helpers::get_numbers(lines.next().unwrap());
+use advent_of_code::{
+    error::{AocError, AocResult},
+    parse::{self, Cursor, ParseResult},
+};
+
+// Cursor at the numbers after `key:`, moving to the next line
+fn numbers<'a>(cursor: &mut Cursor<'a>, key: &str) -> ParseResult<Cursor<'a>> {
+    cursor.skip_whitespace();
+    cursor.tag(key)?;
+    cursor.char(':')?;
+    let start = *cursor;
+    cursor.line();
+    Ok(start)
+}
+
+// Cursors at the times and at the distances
+fn parse_input(input: &str) -> ParseResult<(Cursor<'_>, Cursor<'_>)> {
+    let mut cursor = Cursor::new(input);
+    let times = numbers(&mut cursor, "Time")?;
+    let distances = numbers(&mut cursor, "Distance")?;
+    cursor.end()?;
+    Ok((times, distances))
+}
+
+fn integers(start: Cursor) -> ParseResult<Vec<usize>> {
+    let mut cursor = start;
+    parse::integers(cursor.line()).map_err(|e| e.within(&start))
+}
+
+// Ignore the spaces, the numbers form a single one
+fn kerned(start: Cursor) -> ParseResult<usize> {
+    let mut cursor = start;
+    let text = cursor.line();
+    text.replace(' ', "")
+        .parse()
+        .map_err(|_| start.error(format!("Expected a single number, found {:?}", text.trim())))
+}
+
+pub fn part_one(input: &str) -> AocResult<u32> {
+    let (times, distances) = parse_input(input)?;
+    let times = integers(times)?;
+    let distances = integers(distances)?;
+    if times.len() != distances.len() {
+        return Err(AocError::assumption("there are as many distances as times"));
+    }
 
     let matches = times.iter().zip(distances.iter());
 
-    Some(matches.map(|(tmax, dmax)| find_times(&tmax, &dmax)).product::<u32>())
+    Ok(matches
+        .map(|(tmax, dmax)| find_times(&tmax, &dmax))
+        .product::<u32>())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut lines = input.lines();
-    let tmax = lines.next().unwrap().chars().skip(5).filter(|c| !c.is_whitespace()).collect::<String>().parse::<usize>().unwrap();
-    let dmax = lines.next().unwrap().chars().skip(9).filter(|c| !c.is_whitespace()).collect::<String>().parse::<usize>().unwrap();
-    
-    Some(find_times(&tmax, &dmax))
+pub fn part_two(input: &str) -> AocResult<u32> {
+    let (time, distance) = parse_input(input)?;
+
+    Ok(find_times(&kerned(time)?, &kerned(distance)?))
 }
 
 fn find_times(tmax: &usize, dmax: &usize) -> u32 {
@@ -33,12 +74,30 @@ mod tests {
     #[test]
     fn test_part_one_example() {
         let input = advent_of_code::read_file("examples", 6);
-        assert_eq!(part_one(&input), Some(288));
+        assert_eq!(part_one(&input), Ok(288));
     }
 
     #[test]
     fn test_part_two_example() {
         let input = advent_of_code::read_file("examples", 6);
-        assert_eq!(part_two(&input), Some(71503));
+        assert_eq!(part_two(&input), Ok(71503));
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let mut cursor = Cursor::new("Time: 7 15 30\n");
+        cursor.line();
+        let error = AocError::Parse(cursor.error("Expected \"Distance\""));
+        assert_eq!(Err(error), part_one("Time: 7 15 30\n"));
+    }
+
+    #[test]
+    fn test_invalid_number() {
+        let input = "Time: 7 15\nDistance: 9 x";
+        let mut cursor = Cursor::new(input);
+        cursor.line();
+        cursor.tag("Distance:").unwrap();
+        let error = cursor.error("Expected a single number, found \"9 x\"");
+        assert_eq!(Err(AocError::Parse(error)), part_two(input));
     }
 }
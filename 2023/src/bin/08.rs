@@ -1,87 +1,106 @@
-use std::collections::HashMap;
-
-use num::Integer;
-use regex::Regex;
-
-fn parse_input(input: &str) -> (Vec<char>, HashMap<&str, (&str, &str)>) {
-    let mut lines = input.lines();
-    let instructions: Vec<char> = lines.next().unwrap().chars().collect();
-    lines.next();
-
-    let re = Regex::new(r"(\w+) = \((\w+), (\w+)\)").unwrap();
+use advent_of_code::{
+    assumptions::{self, Assumption},
+    error::{AocError, AocResult},
+    graph::Graph,
+    math,
+    parse::Cursor,
+};
+
+// Instructions, then the nodes with their left and right successors (in this order)
+type Network = (Vec<char>, Graph<(), ()>);
+
+fn parse_input(input: &str) -> AocResult<Network> {
+    let mut cursor = Cursor::new(input);
+    let instructions = cursor.take_while(|c| c == 'L' || c == 'R');
+    let end = cursor;
+    if instructions.is_empty() || !cursor.line().trim().is_empty() {
+        return Err(end.error("Expected L and R instructions").into());
+    }
 
-    let nodes: HashMap<&str, (&str, &str)> = lines
-        .map(|l| {
-            let cap = re.captures(l).unwrap();
-            (
-                cap.get(1).unwrap().as_str(),
-                (cap.get(2).unwrap().as_str(), cap.get(3).unwrap().as_str()),
-            )
-        })
-        .collect();
+    let nodes = Graph::parse_adjacency(cursor.rest()).map_err(|e| e.within(&cursor))?;
+    if nodes.ids().any(|n| nodes.successors(n).len() != 2) {
+        return Err(AocError::assumption(
+            "every node has a left and a right successor",
+        ));
+    }
+    Ok((instructions.chars().collect(), nodes))
+}
 
-    (instructions, nodes)
+fn next(nodes: &Graph<(), ()>, current: u32, instruction: char) -> u32 {
+    if instruction == 'L' {
+        nodes.successors(current)[0].0
+    } else {
+        nodes.successors(current)[1].0
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (instructions, nodes) = parse_input(input);
+pub fn part_one(input: &str) -> AocResult<u32> {
+    let (instructions, nodes) = parse_input(input)?;
 
-    let mut current = "AAA";
+    let missing = || AocError::assumption("there are nodes AAA and ZZZ");
+    let mut current = nodes.id("AAA").ok_or_else(missing)?;
+    let end = nodes.id("ZZZ").ok_or_else(missing)?;
     let mut num = 0;
-    while current != "ZZZ" {
-        let instruction = instructions[num % instructions.len()];
-
-        if instruction == 'L' {
-            current = nodes[current].0;
-        } else {
-            current = nodes[current].1;
-        }
-
+    while current != end {
+        current = next(&nodes, current, instructions[num % instructions.len()]);
         num = num + 1;
     }
 
-    Some(num as u32)
+    Ok(num as u32)
 }
 
+// End node first reached from the start, and after how many steps
 fn solve_part_two_single_path(
-    start: &str,
+    start: u32,
     instructions: &Vec<char>,
-    nodes: &HashMap<&str, (&str, &str)>,
-) -> usize {
+    nodes: &Graph<(), ()>,
+) -> (u32, usize) {
     let mut current = start;
     let mut num = 0;
-    while !current.ends_with("Z") {
-        let instruction = instructions[num % instructions.len()];
-
-        if instruction == 'L' {
-            current = nodes[current].0;
-        } else {
-            current = nodes[current].1;
-        }
-
+    while !nodes.name(current).ends_with("Z") {
+        current = next(nodes, current, instructions[num % instructions.len()]);
         num = num + 1;
     }
 
-    num
+    (current, num)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let (instructions, nodes) = parse_input(input);
+// The network, with the end node and path length of every ghost
+type Ghosts = (Network, Vec<(u32, usize)>);
 
-    // Tried bruteforcing, but with cycles its better to solve each one independently,
-    // then find the lowest common multuplier to get where the loops will end together
-    let current: Vec<&&str> = nodes.keys().filter(|n| n.ends_with("A")).collect();
-    let lengths: Vec<usize> = current
-        .iter()
+fn ghosts(input: &str) -> AocResult<Ghosts> {
+    let (instructions, nodes) = parse_input(input)?;
+    let paths = nodes
+        .ids()
+        .filter(|&n| nodes.name(n).ends_with("A"))
         .map(|start| solve_part_two_single_path(start, &instructions, &nodes))
         .collect();
+    Ok(((instructions, nodes), paths))
+}
 
-    let mut num = lengths[0];
-    for i in 1..lengths.len() {
-        num = num.lcm(&lengths[i]);
-    }
+// The LCM only gives the answer if every ghost then loops back to its end node with
+// the same number of steps as it took to reach it
+const ASSUMPTIONS: [Assumption<Ghosts>; 1] = [Assumption::new(
+    "each ghost cycles back to its end node in as many steps as it took to reach it",
+    |((instructions, nodes), paths)| {
+        paths.iter().all(|&(end, length)| {
+            let back = (length..2 * length).fold(end, |current, num| {
+                next(nodes, current, instructions[num % instructions.len()])
+            });
+            back == end
+        })
+    },
+)];
+
+pub fn part_two(input: &str) -> AocResult<u64> {
+    let ghosts = ghosts(input)?;
+    assumptions::check(&ghosts, &ASSUMPTIONS)?;
+
+    // Tried bruteforcing, but with cycles its better to solve each one independently,
+    // then find the lowest common multuplier to get where the loops will end together
+    let lengths = ghosts.1.iter().map(|&(_, length)| length as u64);
 
-    Some(num as u64)
+    math::lcm_all(lengths).ok_or(AocError::assumption("the answer fits in 64 bits"))
 }
 
 fn main() {
@@ -97,18 +116,37 @@ mod tests {
     #[test]
     fn test_part_one_1() {
         let input = "RL\n\nAAA = (BBB, CCC)\nBBB = (DDD, EEE)\nCCC = (ZZZ, GGG)\nDDD = (DDD, DDD)\nEEE = (EEE, EEE)\nGGG = (GGG, GGG)\nZZZ = (ZZZ, ZZZ)";
-        assert_eq!(part_one(&input), Some(2));
+        assert_eq!(part_one(&input), Ok(2));
     }
 
     #[test]
     fn test_part_one_2() {
         let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
-        assert_eq!(part_one(&input), Some(6));
+        assert_eq!(part_one(&input), Ok(6));
     }
 
     #[test]
     fn test_part_two() {
         let input = "LR\n\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)";
-        assert_eq!(part_two(&input), Some(6));
+        assert_eq!(part_two(&input), Ok(6));
+    }
+
+    #[test]
+    fn test_assumptions() {
+        // The ghost reaches 11Z in 1 step, but then needs 2 to come back
+        let input = "L\n\n11A = (11Z, XXX)\n11Z = (11B, XXX)\n11B = (11Z, XXX)\nXXX = (XXX, XXX)";
+        assert_eq!(
+            vec![ASSUMPTIONS[0].description],
+            assumptions::violated(&ghosts(input).unwrap(), &ASSUMPTIONS)
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let Err(AocError::Parse(error)) = parse_input("LRX\n\nAAA = (ZZZ, ZZZ)") else {
+            panic!("LRX is not an instruction");
+        };
+        assert_eq!((1, 3), (error.line, error.column));
+        assert!(part_one("L\n\nAAA = (BBB, BBB)\nZZZ = (ZZZ, ZZZ)").is_err());
     }
 }
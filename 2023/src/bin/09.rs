@@ -1,66 +1,31 @@
-fn differences(input: &Vec<i32>) -> Vec<i32> {
-    (1..input.len()).map(|i| input[i] - input[i - 1]).collect()
-}
-
-fn generate_differences_list(input: Vec<i32>) -> Vec<Vec<i32>> {
-    let mut diffs: Vec<Vec<i32>> = vec![input];
-
-    // Generate triangle
-    loop {
-        diffs.push(differences(&diffs[diffs.len() - 1]));
-        if diffs[diffs.len() - 1].iter().all(|v| *v == 0) {
-            break;
+use advent_of_code::{
+    error::{AocError, AocResult},
+    parse::{self, Cursor},
+    sequences,
+};
+
+// Sum of the values at `x` of every history
+fn extrapolate_all(input: &str, x: impl Fn(&[i128]) -> i128) -> AocResult<i128> {
+    let mut cursor = Cursor::new(input);
+    let mut sum = 0;
+    while !cursor.is_empty() {
+        let start = cursor;
+        let history: Vec<i128> = parse::integers(cursor.line()).map_err(|e| e.within(&start))?;
+        if history.is_empty() {
+            return Err(start.error("Expected integers").into());
         }
+        sum += sequences::extrapolate(&history, x(&history))
+            .ok_or(AocError::assumption("the values fit in 128 bits"))?;
     }
-    diffs
-}
-
-fn next_value(input: Vec<i32>) -> i32 {
-    let mut diffs = generate_differences_list(input);
-    let num_lines = diffs.len();
-
-    diffs[num_lines - 1].push(0);
-
-    for i in (0..(num_lines - 1)).rev() {
-        let new_val = diffs[i + 1].last().unwrap() + diffs[i].last().unwrap();
-        diffs[i].push(new_val);
-    }
-
-    *diffs[0].last().unwrap()
-}
-
-fn prev_value(input: Vec<i32>) -> i32 {
-    let mut diffs = generate_differences_list(input);
-    let num_lines = diffs.len();
-
-    diffs[num_lines - 1].push(0);
-
-    for i in (0..(num_lines - 1)).rev() {
-        let new_val = diffs[i][0] - diffs[i + 1][0];
-        diffs[i].insert(0, new_val);
-    }
-
-    diffs[0][0]
+    Ok(sum)
 }
 
-pub fn part_one(input: &str) -> Option<i32> {
-    let sum = input
-        .lines()
-        .map(|l| advent_of_code::helpers::get_numbers_signed(l))
-        .map(|v| next_value(v))
-        .sum();
-
-    Some(sum)
+pub fn part_one(input: &str) -> AocResult<i128> {
+    extrapolate_all(input, |history| history.len() as i128)
 }
 
-pub fn part_two(input: &str) -> Option<i32> {
-    let sum = input
-        .lines()
-        .map(|l| advent_of_code::helpers::get_numbers_signed(l))
-        .map(|v| prev_value(v))
-        .sum();
-
-    Some(sum)
+pub fn part_two(input: &str) -> AocResult<i128> {
+    extrapolate_all(input, |_| -1)
 }
 
 fn main() {
@@ -76,12 +41,20 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", 9);
-        assert_eq!(part_one(&input), Some(114));
+        assert_eq!(part_one(&input), Ok(114));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 9);
-        assert_eq!(part_two(&input), Some(2));
+        assert_eq!(part_two(&input), Ok(2));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut cursor = Cursor::new("0 3 6 9\n");
+        cursor.line();
+        let error = AocError::Parse(cursor.error("Expected integers"));
+        assert_eq!(Err(error), part_one("0 3 6 9\n\n1 3 6"));
     }
 }
@@ -8,12 +8,14 @@ use std::{
     process,
 };
 
-const MODULE_TEMPLATE: &str = r###"pub fn part_one(input: &str) -> Option<u32> {
-    None
+const MODULE_TEMPLATE: &str = r###"use advent_of_code::error::{AocError, AocResult};
+
+pub fn part_one(input: &str) -> AocResult<u32> {
+    Err(AocError::NoSolution)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> AocResult<u32> {
+    Err(AocError::NoSolution)
 }
 
 fn main() {
@@ -29,13 +31,13 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), Err(AocError::NoSolution));
     }
 
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), Err(AocError::NoSolution));
     }
 }
 "###;
@@ -7,6 +7,9 @@ use std::env;
 use std::fs;
 
 pub mod helpers;
+pub mod sequences;
+
+pub use aoc_common::{assumptions, error, graph, interval, math, parse};
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -15,23 +18,24 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 #[macro_export]
 macro_rules! solve {
     ($part:expr, $solver:ident, $input:expr) => {{
+        use advent_of_code::error::Answer;
         use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
         use std::fmt::Display;
         use std::time::Instant;
 
-        fn print_result<T: Display>(func: impl FnOnce(&str) -> Option<T>, input: &str) {
+        fn print_result<T: Display, A: Answer<T>>(func: impl FnOnce(&str) -> A, input: &str) {
             let timer = Instant::now();
-            let result = func(input);
+            let result = func(input).into_result();
             let elapsed = timer.elapsed();
             match result {
-                Some(result) => {
+                Ok(result) => {
                     println!(
                         "{} {}(elapsed: {:.2?}){}",
                         result, ANSI_ITALIC, elapsed, ANSI_RESET
                     );
                 }
-                None => {
-                    println!("not solved.")
+                Err(e) => {
+                    println!("failed: {}", e)
                 }
             }
         }
@@ -0,0 +1,180 @@
+//! Sequences given by a polynomial: finite differences, extrapolation and exact
+//! interpolation.
+
+use num::{rational::Ratio, Integer, Zero};
+
+pub type Rational = Ratio<i128>;
+
+/// Differences between consecutive values.
+pub fn differences(values: &[i128]) -> Vec<i128> {
+    values.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+// First value of every row of differences, down to the first constant row
+fn leading_differences(values: &[i128]) -> Vec<i128> {
+    let mut row = values.to_vec();
+    let mut leading = vec![];
+    while let Some(&first) = row.first() {
+        leading.push(first);
+        if row.iter().all(|&v| v == first) {
+            break;
+        }
+        row = differences(&row);
+    }
+    leading
+}
+
+/// Smallest degree of a polynomial giving the values at `0, 1, ...`, which is
+/// at most `len - 1` (only confirmed by the values when it is lower). `None`
+/// without values.
+pub fn degree(values: &[i128]) -> Option<usize> {
+    leading_differences(values).len().checked_sub(1)
+}
+
+/// Value at index `x` (which can be negative or past the end) of the polynomial
+/// of smallest degree giving `values` at `0, 1, ...`, with Newton's forward
+/// differences: `f(x) = sum of Δᵏf(0) * C(x, k)`. `None` without values or on
+/// overflow.
+pub fn extrapolate(values: &[i128], x: i128) -> Option<i128> {
+    let mut value: i128 = 0;
+    // C(x, k), which is an integer for any integer x
+    let mut binomial: i128 = 1;
+    let leading = leading_differences(values);
+    for (k, difference) in leading.iter().enumerate() {
+        if k > 0 {
+            // C(x, k) = C(x, k - 1) * (x - k + 1) / k, dividing first so only the
+            // result can overflow
+            let k = k as i128;
+            let factor = x.checked_sub(k - 1)?;
+            let common = factor.gcd(&k);
+            binomial = (binomial / (k / common)).checked_mul(factor / common)?;
+        }
+        value = value.checked_add(difference.checked_mul(binomial)?)?;
+    }
+    (!leading.is_empty()).then_some(value)
+}
+
+/// Value at `x` of the polynomial going through all the `(x, y)` points, with
+/// Lagrange's formula. `None` when two points have the same `x`.
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Option<Rational> {
+    let mut value = Rational::zero();
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = Rational::from_integer(yi);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i != j {
+                if xi == xj {
+                    return None;
+                }
+                term *= Rational::new(x - xj, xi - xj);
+            }
+        }
+        value += term;
+    }
+    Some(value)
+}
+
+/// Polynomial with exact rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// From the constant term up.
+    pub coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    /// Polynomial of smallest degree going through all the `(x, y)` points, with
+    /// Newton's divided differences. `None` when two points have the same `x`.
+    pub fn fit(points: &[(i128, i128)]) -> Option<Self> {
+        let xs: Vec<Rational> = points.iter().map(|&(x, _)| Rational::from(x)).collect();
+        let mut divided: Vec<Rational> = points.iter().map(|&(_, y)| Rational::from(y)).collect();
+        for level in 1..points.len() {
+            for i in (level..points.len()).rev() {
+                let dx = xs[i] - xs[i - level];
+                if dx.is_zero() {
+                    return None;
+                }
+                divided[i] = (divided[i] - divided[i - 1]) / dx;
+            }
+        }
+
+        // Horner on the Newton form: c0 + (x - x0) * (c1 + (x - x1) * (...))
+        let mut coefficients: Vec<Rational> = vec![];
+        for (c, x) in divided.iter().zip(&xs).rev() {
+            let mut next = vec![*c];
+            next.extend(coefficients.iter().copied());
+            for (i, coefficient) in coefficients.iter().enumerate() {
+                next[i] -= x * coefficient;
+            }
+            coefficients = next;
+        }
+
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Some(Self { coefficients })
+    }
+
+    /// Degree, `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn evaluate(&self, x: Rational) -> Rational {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Rational::zero(), |value, c| value * x + c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extrapolate() {
+        let values = [10, 13, 16, 21, 30, 45];
+        assert_eq!(Some(3), degree(&values));
+        assert_eq!(Some(68), extrapolate(&values, 6));
+        assert_eq!(Some(5), extrapolate(&values, -1));
+        assert_eq!(Some(45), extrapolate(&values, 5));
+
+        // n², far in both directions
+        let squares = [0, 1, 4];
+        assert_eq!(Some(2), degree(&squares));
+        assert_eq!(Some(1_000_000_000_000), extrapolate(&squares, 1_000_000));
+        assert_eq!(Some(49), extrapolate(&squares, -7));
+
+        assert_eq!(Some(0), degree(&[7, 7, 7]));
+        assert_eq!(None, degree(&[]));
+        assert_eq!(None, extrapolate(&[], 3));
+        assert_eq!(None, extrapolate(&[0, 1, 4], i128::MAX / 2));
+
+        // Close to the limit, only the terms in use must fit
+        assert_eq!(Some(1 << 120), extrapolate(&squares, 1 << 60));
+        let x: i128 = 3 << 62;
+        assert_eq!(Some((x / 2) * (x - 1)), extrapolate(&[0, 0, 1], x));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // 3x² - 2x + 1 at a few uneven points
+        let points = [(-2, 17), (1, 2), (4, 41)];
+        assert_eq!(Some(Rational::from(9)), lagrange(&points, 2));
+        assert_eq!(Some(Rational::from(1)), lagrange(&points, 0));
+
+        let polynomial = Polynomial::fit(&points).unwrap();
+        let expected: Vec<Rational> = [1, -2, 3].map(Rational::from).to_vec();
+        assert_eq!(expected, polynomial.coefficients);
+        assert_eq!(Some(2), polynomial.degree());
+        assert_eq!(
+            Rational::new(2, 3),
+            polynomial.evaluate(Rational::new(1, 3))
+        );
+
+        // Collinear points give a line
+        let line = Polynomial::fit(&[(0, 1), (1, 3), (5, 11)]).unwrap();
+        assert_eq!(Some(1), line.degree());
+        assert_eq!(None, Polynomial::fit(&[(1, 1), (1, 2)]));
+        assert_eq!(None, lagrange(&[(1, 1), (1, 2)], 0));
+    }
+}
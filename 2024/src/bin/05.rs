@@ -1,69 +1,80 @@
-use std::{cmp::Ordering, collections::HashSet, fs};
-
-fn load_inputs(input: &str) -> (HashSet<(u32, u32)>, Vec<Vec<u32>>) {
-    let mut lines = input.lines();
-
-    let mut rules: HashSet<(u32, u32)> = HashSet::new();
+use std::fs;
+
+use advent_of_code::{
+    dag,
+    error::{AocError, AocResult},
+    graph::Graph,
+    parse::{Cursor, ParseResult},
+};
+
+// Id of the page read by the cursor, added if no rule mentions it yet
+fn page(cursor: &mut Cursor, pages: &mut Graph<u32, ()>) -> ParseResult<u32> {
+    let number: u32 = cursor.integer()?;
+    let id = pages.add_node(&number.to_string());
+    *pages.node_mut(id) = number;
+    Ok(id)
+}
 
-    for line in lines.by_ref() {
-        if line.is_empty() {
-            break;
+// Rules are edges between pages (every node holds its page number), sequences are
+// made of the pages ids
+type Pages = (Graph<u32, ()>, Vec<Vec<u32>>);
+
+fn load_inputs(input: &str) -> ParseResult<Pages> {
+    let mut rules = Graph::new();
+    let mut sequences = vec![];
+    let mut cursor = Cursor::new(input);
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let first = page(&mut cursor, &mut rules)?;
+        if cursor.peek() == Some('|') {
+            cursor.char('|')?;
+            let next = page(&mut cursor, &mut rules)?;
+            rules.add_edge(first, next, ());
+        } else {
+            let mut sequence = vec![first];
+            while cursor.peek() == Some(',') {
+                cursor.char(',')?;
+                sequence.push(page(&mut cursor, &mut rules)?);
+            }
+            sequences.push(sequence);
         }
 
-        let (prev, next) = line.split_once('|').unwrap();
-        let prev: u32 = prev.parse().unwrap();
-        let next: u32 = next.parse().unwrap();
-
-        rules.insert((prev, next));
+        cursor.skip_spaces();
+        if !cursor.is_empty() {
+            cursor.char('\n')?;
+        }
+        cursor.skip_whitespace();
     }
 
-    let sequences: Vec<Vec<u32>> = lines
-        .map(|line| line.split(',').map(|s| s.parse::<u32>().unwrap()).collect())
-        .collect();
-
-    (rules, sequences)
+    Ok((rules, sequences))
 }
 
-fn comparator(rules: &HashSet<(u32, u32)>) -> impl Fn(&u32, &u32) -> Ordering + '_ {
-    move |a: &u32, b: &u32| {
-        if rules.contains(&(*a, *b)) {
-            return Ordering::Less;
-        } else if rules.contains(&(*b, *a)) {
-            return Ordering::Greater;
-        }
-        Ordering::Equal
-    }
+fn middle_page(rules: &Graph<u32, ()>, sequence: &[u32]) -> u32 {
+    *rules.node(sequence[sequence.len() / 2])
 }
 
-fn part_one(input: &str) -> Option<u32> {
-    let (rules, sequences) = load_inputs(input);
-    let comparator = comparator(&rules);
-    Some(
-        sequences
-            .iter()
-            .filter(|s| s.is_sorted_by(|a, b| comparator(a, b).is_le()))
-            .map(|s| s[s.len() / 2])
-            .sum(),
-    )
+fn part_one(input: &str) -> AocResult<u32> {
+    let (rules, sequences) = load_inputs(input)?;
+    Ok(sequences
+        .iter()
+        .filter(|s| dag::is_consistent(&rules, s))
+        .map(|s| middle_page(&rules, s))
+        .sum())
 }
 
-fn part_two(input: &str) -> Option<u32> {
-    let (rules, sequences) = load_inputs(input);
-
-    let comparator = comparator(&rules);
-
-    Some(
-        sequences
-            .iter()
-            .filter(|s| !s.is_sorted_by(|a, b| comparator(a, b).is_le()))
-            .map(|s| {
-                let mut s = s.to_vec();
-                s.sort_by(&comparator);
-                s
-            })
-            .map(|s| s[s.len() / 2])
-            .sum(),
-    )
+fn part_two(input: &str) -> AocResult<u32> {
+    let (rules, sequences) = load_inputs(input)?;
+
+    // The whole set of rules has cycles, but the ones between the pages of a sequence don't
+    let mut sum = 0;
+    for sequence in sequences.iter().filter(|s| !dag::is_consistent(&rules, s)) {
+        let sorted = dag::topological_sort_of(&rules, sequence).map_err(|cycle| {
+            let pages: Vec<&str> = cycle.nodes.iter().map(|&n| rules.name(n)).collect();
+            AocError::assumption(format!("pages {} form a cycle", pages.join("|")))
+        })?;
+        sum += middle_page(&rules, &sorted);
+    }
+    Ok(sum)
 }
 
 fn main() {
@@ -80,12 +91,23 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/05.txt").unwrap();
-        assert_eq!(Some(143), part_one(&input));
+        assert_eq!(Ok(143), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/05.txt").unwrap();
-        assert_eq!(Some(123), part_two(&input));
+        assert_eq!(Ok(123), part_two(&input));
+    }
+
+    #[test]
+    fn unknown_page_test() {
+        // No rule mentions 99
+        let input = "47|53\n97|47\n\n53,47\n97,99,47\n";
+        assert_eq!(Ok(99), part_one(input));
+        assert_eq!(Ok(53), part_two(input));
+
+        let error = load_inputs("47|53\n\n53,x").unwrap_err();
+        assert_eq!((3, 4), (error.line, error.column));
     }
 }
@@ -1,4 +1,9 @@
-use advent_of_code::{coordinate::Coordinate, grid::Grid};
+use advent_of_code::{
+    coordinate::Coordinate,
+    cycle,
+    error::{AocError, AocResult},
+    grid::Grid,
+};
 use std::{collections::HashSet, fs};
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
@@ -70,28 +75,31 @@ fn detect_loop(initial: Coordinate, obstacle: Coordinate, grid: &Grid<char>) ->
     let mut grid = (*grid).clone();
     grid.set(obstacle, '#');
 
-    let mut positions: HashSet<(Coordinate, Direction)> = HashSet::new();
-
     let mut guard = Guard {
         position: initial,
         direction: Direction::North,
     };
 
-    guard.step(&grid);
-
-    while grid.is_inside(&guard.position) && !positions.contains(&(guard.position, guard.direction))
-    {
-        positions.insert((guard.position, guard.direction));
+    // Either the guard walks out of the grid, or comes back to a previous state
+    let step = |guard: &mut Guard| {
         guard.step(&grid);
-    }
+        grid.is_inside(&guard.position)
+    };
+    cycle::detect(&mut guard, step, |g| (g.position, g.direction)).is_some()
+}
 
-    grid.is_inside(&guard.position)
+// The map and the starting position of the guard
+fn read_map(input: &str) -> AocResult<(Grid<char>, Coordinate)> {
+    let grid = Grid::parse_chars(input, ".#^")?;
+    let initial = grid
+        .find_first('^')
+        .ok_or_else(|| AocError::assumption("the guard '^' is on the map"))?;
+    Ok((grid, initial))
 }
 
-fn part_one(input: &str) -> Option<u32> {
-    let grid = Grid::<char>::new_chars(input);
-    let initial = grid.find_first('^').unwrap();
-    Some(guard_steps(initial, &grid).len() as u32)
+fn part_one(input: &str) -> AocResult<u32> {
+    let (grid, initial) = read_map(input)?;
+    Ok(guard_steps(initial, &grid).len() as u32)
 }
 
 fn guard_steps(initial: Coordinate, grid: &Grid<char>) -> HashSet<Coordinate> {
@@ -110,19 +118,15 @@ fn guard_steps(initial: Coordinate, grid: &Grid<char>) -> HashSet<Coordinate> {
     positions
 }
 
-fn part_two(input: &str) -> Option<u32> {
-    let grid = Grid::new_chars(input);
-
-    let initial = grid.find_first('^').unwrap();
+fn part_two(input: &str) -> AocResult<u32> {
+    let (grid, initial) = read_map(input)?;
 
     let positions = guard_steps(initial, &grid);
 
-    Some(
-        positions
-            .iter()
-            .filter(|pos| detect_loop(initial, **pos, &grid))
-            .count() as u32,
-    )
+    Ok(positions
+        .iter()
+        .filter(|pos| detect_loop(initial, **pos, &grid))
+        .count() as u32)
 }
 
 fn main() {
@@ -139,12 +143,27 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/06.txt").unwrap();
-        assert_eq!(Some(41), part_one(&input));
+        assert_eq!(Ok(41), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/06.txt").unwrap();
-        assert_eq!(Some(6), part_two(&input));
+        assert_eq!(Ok(6), part_two(&input));
+    }
+
+    #[test]
+    fn invalid_map_test() {
+        let error = match part_one("..#\n.^.\n.x.\n") {
+            Err(AocError::Parse(error)) => error,
+            result => panic!("Expected a parse error, got {:?}", result),
+        };
+        assert_eq!((3, 2), (error.line, error.column));
+        assert!(matches!(part_one("..#\n.^\n"), Err(AocError::Parse(_))));
+        assert!(matches!(
+            part_one("..#\n...\n"),
+            Err(AocError::Assumption(_))
+        ));
+        assert_eq!(Ok(2), part_one("..\r\n.^\r\n"));
     }
 }
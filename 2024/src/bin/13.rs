@@ -1,7 +1,10 @@
 use std::fs;
 
-use itertools::Itertools;
-use regex::Regex;
+use advent_of_code::{
+    error::AocResult,
+    math,
+    parse::{self, Cursor, ParseResult},
+};
 
 #[derive(Clone, Copy, Debug)]
 struct Machine {
@@ -11,58 +14,95 @@ struct Machine {
 }
 
 impl Machine {
-    pub fn parse(lines: Vec<&str>) -> Self {
-        let input = lines.join("");
-
-        let re = Regex::new(r"\d+").unwrap();
-        let numbers: Vec<u64> = re
-            .find_iter(&input)
-            .filter_map(|m| m.as_str().parse::<u64>().ok())
-            .collect();
-
-        Self {
-            a: (numbers[1], numbers[0]),
-            b: (numbers[3], numbers[2]),
-            prize: (numbers[5], numbers[4]),
+    pub fn parse(block: &str) -> ParseResult<Self> {
+        match parse::integers(block)?[..] {
+            [ax, ay, bx, by, px, py] => Ok(Self {
+                a: (ay, ax),
+                b: (by, bx),
+                prize: (py, px),
+            }),
+            _ => Err(Cursor::new(block).error("Expected 6 integers")),
         }
     }
 }
 
 fn min_prize_moves(machine: &Machine) -> Option<u64> {
-    // Cramer rule, see wikipedia
-    let a1 = machine.a.0 as i64;
-    let a2 = machine.a.1 as i64;
-    let b1 = machine.b.0 as i64;
-    let b2 = machine.b.1 as i64;
-    let c1 = machine.prize.0 as i64;
-    let c2 = machine.prize.1 as i64;
-
-    let a = (b2 * c1 - b1 * c2) as f64 / (a1 * b2 - b1 * a2) as f64;
-    let b = (a1 * c2 - a2 * c1) as f64 / (a1 * b2 - b1 * a2) as f64;
-
-    if a >= 0f64 && b >= 0f64 && a.floor() == a && b.floor() == b {
-        Some((a * 3f64 + b).floor() as u64)
-    } else {
-        None
+    let matrix = [
+        vec![machine.a.0 as i64, machine.b.0 as i64],
+        vec![machine.a.1 as i64, machine.b.1 as i64],
+    ];
+    let prize = [machine.prize.0 as i64, machine.prize.1 as i64];
+
+    if math::determinant(&matrix) != 0 {
+        // The buttons move in different directions, a single way to reach the prize
+        let presses = math::solve_integer(&matrix, &prize)?;
+        return match presses[..] {
+            [a, b] if a >= 0 && b >= 0 => Some(a as u64 * 3 + b as u64),
+            _ => None,
+        };
+    }
+
+    // Both buttons move along the same line, which must go through the prize
+    let cross = |(y1, x1): (u64, u64), (y2, x2): (u64, u64)| {
+        y1 as i128 * x2 as i128 - x1 as i128 * y2 as i128
+    };
+    if cross(machine.a, machine.prize) != 0 || cross(machine.b, machine.prize) != 0 {
+        return None;
+    }
+    // Then moving along one axis moves along the other, unless they never move on it
+    match (matrix[0][0], matrix[0][1]) {
+        (0, 0) if prize[0] != 0 => None,
+        (0, 0) => min_line_presses(matrix[1][0], matrix[1][1], prize[1]),
+        (a, b) => min_line_presses(a, b, prize[0]),
     }
 }
 
-fn part_one(input: &str) -> Option<u64> {
-    let machines: Vec<Machine> = input
-        .lines()
-        .chunks(4)
+/// Cheapest presses of buttons moving by `a` and `b` (both at least 0) to move by
+/// exactly `target`, pressing A costing 3 and B costing 1.
+fn min_line_presses(a: i64, b: i64, target: i64) -> Option<u64> {
+    let presses = match (a, b) {
+        (0, 0) => (target == 0).then_some((0, 0))?,
+        (0, b) => (0, (target % b == 0).then_some(target / b)?),
+        (a, 0) => ((target % a == 0).then_some(target / a)?, 0),
+        (a, b) => {
+            let (g, x, y) = math::extended_gcd(a, b);
+            if target % g != 0 {
+                return None;
+            }
+            // Every solution is (a0 + k * step_a, b0 - k * step_b), both must be positive
+            let (a0, b0) = (
+                x as i128 * (target / g) as i128,
+                y as i128 * (target / g) as i128,
+            );
+            let (step_a, step_b) = ((b / g) as i128, (a / g) as i128);
+            let min_k = -a0.div_euclid(step_a);
+            let max_k = b0.div_euclid(step_b);
+            if min_k > max_k {
+                return None;
+            }
+            // The cost changes by 3 * step_a - step_b with k, the cheapest is at a bound
+            let k = if 3 * step_a > step_b { min_k } else { max_k };
+            ((a0 + k * step_a) as i64, (b0 - k * step_b) as i64)
+        }
+    };
+    Some(presses.0 as u64 * 3 + presses.1 as u64)
+}
+
+fn parse_input(input: &str) -> ParseResult<Vec<Machine>> {
+    parse::blocks(input)
         .into_iter()
-        .map(|chunk| Machine::parse(chunk.collect()))
-        .collect();
-    Some(machines.iter().filter_map(min_prize_moves).sum())
+        .map(Machine::parse)
+        .collect()
 }
 
-fn part_two(input: &str) -> Option<u64> {
-    let machines: Vec<Machine> = input
-        .lines()
-        .chunks(4)
+fn part_one(input: &str) -> AocResult<u64> {
+    let machines = parse_input(input)?;
+    Ok(machines.iter().filter_map(min_prize_moves).sum())
+}
+
+fn part_two(input: &str) -> AocResult<u64> {
+    let machines: Vec<Machine> = parse_input(input)?
         .into_iter()
-        .map(|chunk| Machine::parse(chunk.collect()))
         .map(|machine| Machine {
             a: machine.a,
             b: machine.b,
@@ -72,7 +112,7 @@ fn part_two(input: &str) -> Option<u64> {
             ),
         })
         .collect();
-    Some(machines.iter().filter_map(min_prize_moves).sum())
+    Ok(machines.iter().filter_map(min_prize_moves).sum())
 }
 
 fn main() {
@@ -86,9 +126,27 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn collinear_test() {
+        let machine = |a, b, prize| Machine { a, b, prize };
+        assert_eq!(Some(2), min_prize_moves(&machine((1, 1), (2, 2), (4, 4))));
+        assert_eq!(Some(7), min_prize_moves(&machine((6, 6), (1, 1), (13, 13))));
+        assert_eq!(Some(7), min_prize_moves(&machine((0, 4), (0, 6), (0, 14))));
+        assert_eq!(None, min_prize_moves(&machine((1, 1), (2, 2), (4, 5))));
+        assert_eq!(None, min_prize_moves(&machine((4, 4), (6, 6), (9, 9))));
+        assert_eq!(None, min_prize_moves(&machine((0, 0), (0, 0), (1, 1))));
+        assert_eq!(None, min_prize_moves(&machine((0, 0), (0, 0), (1, 0))));
+    }
+
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/13.txt").unwrap();
-        assert_eq!(Some(480), part_one(&input));
+        assert_eq!(Ok(480), part_one(&input));
+    }
+
+    #[test]
+    fn part_two_test() {
+        let input = fs::read_to_string("examples/13.txt").unwrap();
+        assert_eq!(Ok(875318608908), part_two(&input));
     }
 }
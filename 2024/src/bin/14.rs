@@ -1,8 +1,16 @@
-use std::{collections::HashSet, fs};
-
-use advent_of_code::coordinate::Coordinate;
+use std::{collections::HashSet, fs, num::NonZeroU32};
+
+use advent_of_code::{
+    coordinate::Coordinate,
+    cycle,
+    error::{AocError, AocResult},
+    params::Params,
+};
+use aoc_derive::AocParse;
 use itertools::Itertools;
-use regex::Regex;
+
+/// Puzzle parameters, which the examples shrink.
+const PARAMS: &[&str] = &["height", "width"];
 
 #[derive(Debug)]
 struct Limits {
@@ -11,6 +19,16 @@ struct Limits {
 }
 
 impl Limits {
+    // The robots wrap around, the space cannot be empty
+    pub fn from(params: &Params) -> AocResult<Self> {
+        let height: NonZeroU32 = params.get("height", NonZeroU32::new(103).unwrap())?;
+        let width: NonZeroU32 = params.get("width", NonZeroU32::new(101).unwrap())?;
+        Ok(Self {
+            height: height.get(),
+            width: width.get(),
+        })
+    }
+
     pub fn constrain(&self, coord: &Coordinate) -> Coordinate {
         let y = coord.y;
         let x = coord.x;
@@ -47,26 +65,14 @@ impl Limits {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, AocParse)]
+#[aoc(format = "p={position} v={velocity}")]
 struct Robot {
     position: Coordinate,
     velocity: Coordinate,
 }
 
 impl Robot {
-    pub fn parse(line: &str) -> Self {
-        let re = Regex::new(r"-?\d+").unwrap();
-        let numbers: Vec<i32> = re
-            .find_iter(line)
-            .filter_map(|m| m.as_str().parse::<i32>().ok())
-            .collect();
-
-        Self {
-            position: Coordinate::new(numbers[1], numbers[0]),
-            velocity: Coordinate::new(numbers[3], numbers[2]),
-        }
-    }
-
     pub fn step(&self, limits: &Limits) -> Self {
         let new_coordinate = limits.constrain(&(self.position + self.velocity));
         Self {
@@ -89,28 +95,17 @@ struct Quadrant {
     end: Coordinate,
 }
 
-fn part_one_helper(input: &str, limits: Limits) -> Option<u32> {
-    let initial: Vec<Robot> = input.lines().map(Robot::parse).collect();
+fn part_one(input: &str, params: &Params) -> AocResult<u32> {
+    let limits = Limits::from(params)?;
+    let initial: Vec<Robot> = input.lines().map(str::parse).collect::<Result<_, _>>()?;
     let robots = (0..100).fold(initial, |acc, _| {
         acc.iter().map(|r| r.step(&limits)).collect()
     });
-    Some(
-        limits
-            .quadrants()
-            .iter()
-            .map(|quadrant| robots.iter().filter(|r| r.is_in(quadrant)).count() as u32)
-            .product(),
-    )
-}
-
-fn part_one(input: &str) -> Option<u32> {
-    part_one_helper(
-        input,
-        Limits {
-            height: 103,
-            width: 101,
-        },
-    )
+    Ok(limits
+        .quadrants()
+        .iter()
+        .map(|quadrant| robots.iter().filter(|r| r.is_in(quadrant)).count() as u32)
+        .product())
 }
 
 fn print(step: u32, robots: &Vec<Robot>, limits: &Limits) {
@@ -147,33 +142,36 @@ fn density(robots: &Vec<Robot>) -> u32 {
         .sum()
 }
 
-fn part_two(input: &str) -> Option<u32> {
-    let limits = Limits {
-        height: 103,
-        width: 101,
-    };
-    let initial: Vec<Robot> = input.lines().map(Robot::parse).collect();
+fn part_two(input: &str, params: &Params) -> AocResult<u32> {
+    let limits = Limits::from(params)?;
+    let initial: Vec<Robot> = input.lines().map(str::parse).collect::<Result<_, _>>()?;
+
+    let step = |robots: &Vec<Robot>| robots.iter().map(|r| r.step(&limits)).collect();
 
-    let mut robots: Vec<Robot> = initial.iter().map(|r| r.step(&limits)).collect();
+    // The robots come back to their positions after some steps, so every picture is
+    // seen before the end of the first cycle
+    let cycle = cycle::brent(initial.clone(), step);
 
+    let mut robots = initial;
     let mut scores: Vec<(u32, u32)> = vec![];
 
-    // Tree seen in the first 10000 steps as a small/dense picture in a frame.
+    // Tree seen as a small/dense picture in a frame.
     // Search for the step with the most density (number of positions with adjacent positions)
-    for step in 0..10000 {
-        scores.push((step, density(&robots)));
-        robots = robots.iter().map(|r| r.step(&limits)).collect();
+    for step_idx in 0..(cycle.start + cycle.length) as u32 {
+        scores.push((step_idx, density(&robots)));
+        robots = step(&robots);
     }
 
     scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
 
-    Some(scores[0].0 + 1)
+    Ok(scores[0].0)
 }
 
 fn main() {
     let input = fs::read_to_string("inputs/14.txt").unwrap();
-    advent_of_code::solve(1, &input, part_one);
-    advent_of_code::solve(2, &input, part_two);
+    let params = Params::from_args(PARAMS).map_err(AocError::from);
+    advent_of_code::solve(1, &input, |i| part_one(i, &params.clone()?));
+    advent_of_code::solve(2, &input, |i| part_two(i, &params.clone()?));
 }
 
 #[cfg(test)]
@@ -181,18 +179,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn can_parse_robot() {
+        let robot: Robot = "p=0,4 v=3,-3".parse().unwrap();
+        assert_eq!(Coordinate::new(4, 0), robot.position);
+        assert_eq!(Coordinate::new(-3, 3), robot.velocity);
+
+        let error = "p=0,4 w=3,-3".parse::<Robot>().unwrap_err();
+        assert_eq!((1, 7), (error.line, error.column));
+    }
+
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/14.txt").unwrap();
-        assert_eq!(
-            Some(12),
-            part_one_helper(
-                &input,
-                Limits {
-                    height: 7,
-                    width: 11
-                }
-            )
-        );
+        let params = Params::new().with("height", 7).with("width", 11);
+        assert_eq!(Ok(12), part_one(&input, &params));
     }
 }
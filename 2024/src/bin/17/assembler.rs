@@ -0,0 +1,83 @@
+use advent_of_code::parse::{Cursor, ParseResult};
+
+use crate::device::{OpCode, Operand};
+
+/// Memory of a program written as [disassemble](crate::device::disassemble) shows it.
+pub fn assemble(source: &str) -> ParseResult<Vec<u8>> {
+    let mut cursor = Cursor::new(source);
+    let mut memory = vec![];
+
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let start = cursor;
+        let mnemonic = cursor.word()?.to_lowercase();
+        let op = OpCode::ALL
+            .into_iter()
+            .find(|op| op.mnemonic() == mnemonic)
+            .ok_or_else(|| start.error(format!("Unknown instruction {:?}", mnemonic)))?;
+
+        cursor.skip_spaces();
+        let start = cursor;
+        let operand = match (op.operand(), cursor.peek()) {
+            (Operand::Ignored, None | Some('\n' | '\r')) => 0,
+            (_, None | Some('\n' | '\r')) => return Err(cursor.error("Missing operand")),
+            (operand, _) => match (operand, cursor.word()?.to_lowercase().as_str()) {
+                (Operand::Combo, register @ ("a" | "b" | "c")) => register.as_bytes()[0] - b'a' + 4,
+                (Operand::Combo, value) => match value.parse() {
+                    Ok(value @ 0..=3) => value,
+                    _ => return Err(start.error("Expected 0 to 3 or a register")),
+                },
+                (_, value) => match value.parse() {
+                    Ok(value @ 0..=7) => value,
+                    _ => return Err(start.error("Expected 0 to 7")),
+                },
+            },
+        };
+
+        memory.extend([op as u8, operand]);
+        cursor.skip_whitespace();
+    }
+
+    Ok(memory)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::device::{disassemble, program, Device};
+
+    #[test]
+    fn test_disassemble() {
+        let memory = vec![2, 4, 1, 5, 7, 5, 4, 0, 5, 5, 0, 3, 3, 0];
+        let listing = "bst a\nbxl 5\ncdv b\nbxc\nout b\nadv 3\njnz 0";
+        assert_eq!(listing, disassemble(&memory));
+        assert_eq!(Ok(memory), assemble(listing));
+    }
+
+    #[test]
+    fn test_assemble() {
+        // Outputs the octal digits of A, lowest first
+        let memory = assemble("  BST A\n out b \n\nadv 3\njnz 0\n").unwrap();
+        assert_eq!("Program: 2,4,5,5,0,3,3,0", program(&memory));
+
+        let mut device: Device = format!(
+            "Register A: 83\nRegister B: 0\nRegister C: 0\n\n{}",
+            program(&memory)
+        )
+        .parse()
+        .unwrap();
+        assert_eq!("3,2,1", device.run());
+
+        let error = assemble("adv 3\nout d").unwrap_err();
+        assert_eq!((2, 5), (error.line, error.column));
+        assert!(assemble("bxl").is_err());
+        assert_eq!(
+            Ok(vec![2, 4, 4, 0, 5, 5]),
+            assemble("bst a\r\nbxc\r\nout b\r\n")
+        );
+        let error = assemble("bxc\r\nbxl\r\n").unwrap_err();
+        assert_eq!((2, 4), (error.line, error.column));
+        assert!(assemble("mul 3").is_err());
+    }
+}
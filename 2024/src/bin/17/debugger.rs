@@ -0,0 +1,235 @@
+use std::io::{self, BufRead, Write};
+
+use crate::device::{instruction, Device, TraceStep, REGISTERS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Breakpoint {
+    Ip(usize),
+    // Number of values output
+    Output(usize),
+}
+
+/// Interactive debugger around a device, able to go back to previous steps.
+pub struct Debugger {
+    device: Device,
+    history: Vec<Device>,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<usize>,
+}
+
+const HELP: &str = "Commands:
+  s, step [n]          run n steps (1 by default)
+  b, back [n]          go back n steps
+  c, continue          run until a breakpoint, a watched register changes or the end
+  break ip <n>         stop before running the instruction at n
+  break out <n>        stop once n values are output
+  watch <a|b|c>        stop when the register changes
+  clear                remove every breakpoint and watch
+  p, print             show the registers and the next instruction
+  q, quit";
+
+// Continuing stops there, in case the program never ends
+const MAX_STEPS: usize = 1_000_000;
+
+impl Debugger {
+    pub fn new(device: Device) -> Self {
+        Self {
+            device,
+            history: vec![],
+            breakpoints: vec![],
+            watches: vec![],
+        }
+    }
+
+    pub fn repl(mut self) {
+        println!("{}", self.status());
+        let stdin = io::stdin();
+        loop {
+            print!("> ");
+            io::stdout().flush().unwrap();
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).unwrap() == 0 {
+                return;
+            }
+            if matches!(line.trim(), "q" | "quit") {
+                return;
+            }
+            println!("{}", self.execute(&line));
+        }
+    }
+
+    /// Runs a command, giving what to show.
+    pub fn execute(&mut self, command: &str) -> String {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let count = |idx: usize| words.get(idx).map_or(Some(1), |n| n.parse().ok());
+
+        match words[..] {
+            ["s" | "step", ..] if words.len() <= 2 => match count(1) {
+                Some(n) => self.step(n),
+                None => HELP.to_string(),
+            },
+            ["b" | "back", ..] if words.len() <= 2 => match count(1) {
+                Some(n) => self.back(n),
+                None => HELP.to_string(),
+            },
+            ["c" | "continue"] => self.resume(),
+            ["break", kind, n] => match (kind, n.parse()) {
+                ("ip", Ok(n)) => self.add_breakpoint(Breakpoint::Ip(n)),
+                ("out", Ok(n)) => self.add_breakpoint(Breakpoint::Output(n)),
+                _ => HELP.to_string(),
+            },
+            ["watch", register] => match REGISTERS.iter().position(|r| r.to_string() == register) {
+                Some(r) => {
+                    self.watches.push(r);
+                    format!("Watching {}", register)
+                }
+                None => HELP.to_string(),
+            },
+            ["clear"] => {
+                self.breakpoints.clear();
+                self.watches.clear();
+                "Cleared breakpoints and watches".to_string()
+            }
+            ["p" | "print"] | [] => self.status(),
+            _ => HELP.to_string(),
+        }
+    }
+
+    fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> String {
+        self.breakpoints.push(breakpoint);
+        format!("Breakpoint {:?}", breakpoint)
+    }
+
+    // Next step, remembering the state before it to go back
+    fn trace_step(&mut self) -> Option<TraceStep> {
+        let previous = self.device.clone();
+        let step = self.device.trace_step()?;
+        self.history.push(previous);
+        Some(step)
+    }
+
+    fn step(&mut self, n: usize) -> String {
+        let mut lines: Vec<String> = (0..n)
+            .map_while(|_| self.trace_step())
+            .map(|step| step.to_string())
+            .collect();
+        lines.push(self.status());
+        lines.join("\n")
+    }
+
+    fn back(&mut self, n: usize) -> String {
+        for _ in 0..n {
+            match self.history.pop() {
+                Some(device) => self.device = device,
+                None => break,
+            }
+        }
+        self.status()
+    }
+
+    fn resume(&mut self) -> String {
+        let mut last = None;
+        for _ in 0..MAX_STEPS {
+            let Some(step) = self.trace_step() else {
+                return self.status();
+            };
+
+            let watched = step
+                .changes()
+                .into_iter()
+                .find(|(register, _, _)| self.watches.iter().any(|&r| REGISTERS[r] == *register));
+            let breakpoint = self.breakpoints.iter().find(|b| match b {
+                Breakpoint::Ip(ip) => self.device.ip == *ip && !self.device.ended(),
+                Breakpoint::Output(count) => {
+                    step.output.is_some() && self.device.output.len() == *count
+                }
+            });
+
+            let reason = match (watched, breakpoint) {
+                (Some((register, before, after)), _) => {
+                    format!("Watch: {} changed from {} to {}", register, before, after)
+                }
+                (_, Some(breakpoint)) => format!("Stopped at {:?}", breakpoint),
+                _ => {
+                    last = Some(step);
+                    continue;
+                }
+            };
+            return format!("{}\n{}\n{}", step, reason, self.status());
+        }
+
+        format!(
+            "{}\nStopped after {} steps\n{}",
+            last.map(|s| s.to_string()).unwrap_or_default(),
+            MAX_STEPS,
+            self.status()
+        )
+    }
+
+    fn status(&self) -> String {
+        let device = &self.device;
+        let next = if device.ended() {
+            "halted".to_string()
+        } else {
+            format!(
+                "next {}",
+                instruction(&device.program[device.ip..device.ip + 2])
+            )
+        };
+        let [a, b, c] = device.registers;
+        format!(
+            "ip={} a={} b={} c={} out=[{}] {}",
+            device.ip,
+            a,
+            b,
+            c,
+            device.output(),
+            next
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_debugger() {
+        let device: Device = fs::read_to_string("examples/17_1.txt")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let mut debugger = Debugger::new(device);
+
+        assert_eq!(
+            "  0: adv 1    a: 729 -> 364\n  2: out a    out 4\nip=4 a=364 b=0 c=0 out=[4] next jnz 0",
+            debugger.execute("step 2")
+        );
+        assert_eq!(
+            "ip=2 a=364 b=0 c=0 out=[] next out a",
+            debugger.execute("back")
+        );
+
+        debugger.execute("break out 3");
+        assert!(debugger.execute("c").contains("Stopped at Output(3)"));
+        assert_eq!("4,6,3", debugger.device.output());
+
+        debugger.execute("clear");
+        debugger.execute("break ip 4");
+        debugger.execute("watch a");
+        assert!(debugger
+            .execute("continue")
+            .contains("Watch: a changed from 91 to 45"));
+        assert!(debugger.execute("c").contains("Stopped at Ip(4)"));
+        assert_eq!("4,6,3,5", debugger.device.output());
+
+        debugger.execute("clear");
+        assert!(debugger.execute("c").ends_with("halted"));
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", debugger.device.output());
+        assert_eq!(HELP, debugger.execute("jump 3"));
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use advent_of_code::{
+    parse::{Cursor, ParseError},
+    vm::{Flow, InstructionSet, Machine},
+};
+use itertools::Itertools;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpCode {
+    Adv = 0,
+    Bxl = 1,
+    Bst = 2,
+    Jnz = 3,
+    Bxc = 4,
+    Out = 5,
+    Bdv = 6,
+    Cdv = 7,
+}
+
+impl From<u8> for OpCode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OpCode::Adv,
+            1 => OpCode::Bxl,
+            2 => OpCode::Bst,
+            3 => OpCode::Jnz,
+            4 => OpCode::Bxc,
+            5 => OpCode::Out,
+            6 => OpCode::Bdv,
+            7 => OpCode::Cdv,
+            _ => panic!("Invalid opcode"),
+        }
+    }
+}
+
+pub const REGISTERS: [char; 3] = ['a', 'b', 'c'];
+
+// How an instruction reads its operand
+pub enum Operand {
+    Literal,
+    // 0 to 3 as is, then the registers A, B and C
+    Combo,
+    Ignored,
+}
+
+impl OpCode {
+    pub const ALL: [OpCode; 8] = [
+        OpCode::Adv,
+        OpCode::Bxl,
+        OpCode::Bst,
+        OpCode::Jnz,
+        OpCode::Bxc,
+        OpCode::Out,
+        OpCode::Bdv,
+        OpCode::Cdv,
+    ];
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            OpCode::Adv => "adv",
+            OpCode::Bxl => "bxl",
+            OpCode::Bst => "bst",
+            OpCode::Jnz => "jnz",
+            OpCode::Bxc => "bxc",
+            OpCode::Out => "out",
+            OpCode::Bdv => "bdv",
+            OpCode::Cdv => "cdv",
+        }
+    }
+
+    pub fn operand(&self) -> Operand {
+        match self {
+            OpCode::Bxl | OpCode::Jnz => Operand::Literal,
+            OpCode::Bxc => Operand::Ignored,
+            _ => Operand::Combo,
+        }
+    }
+}
+
+/// One instruction per line, like `adv 3` or `out b`, with the combo operands
+/// reading a register shown as the register.
+pub fn disassemble(memory: &[u8]) -> String {
+    memory.chunks(2).map(instruction).join("\n")
+}
+
+// Opcode and operand (if any) as text
+pub fn instruction(instruction: &[u8]) -> String {
+    let op = OpCode::from(instruction[0]);
+    match (op.operand(), instruction.get(1)) {
+        (_, None) | (Operand::Ignored, Some(0)) => op.mnemonic().to_string(),
+        (Operand::Combo, Some(&r @ 4..=6)) => {
+            format!("{} {}", op.mnemonic(), REGISTERS[(r - 4) as usize])
+        }
+        (Operand::Combo, Some(7)) => format!("{} reserved", op.mnemonic()),
+        (_, Some(operand)) => format!("{} {}", op.mnemonic(), operand),
+    }
+}
+
+/// The `Program: ...` line of an input.
+pub fn program(memory: &[u8]) -> String {
+    format!("Program: {}", memory.iter().join(","))
+}
+
+/// The 3-bit computer, with an opcode and its operand per instruction and the
+/// registers A, B and C.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ThreeBit;
+
+impl InstructionSet for ThreeBit {
+    type Word = u8;
+    type Instruction = (OpCode, u8);
+    type Registers = [u64; 3];
+    type Output = u8;
+
+    fn decode(program: &[u8], ip: usize) -> Option<((OpCode, u8), usize)> {
+        match *program.get(ip..ip + 2)? {
+            [op, operand] => Some(((OpCode::from(op), operand), 2)),
+            _ => None,
+        }
+    }
+
+    fn execute(&(op, operand): &(OpCode, u8), device: &mut Machine<Self>) -> Flow {
+        let registers = &mut device.registers;
+        let combo = match operand {
+            0..=3 => operand as u64,
+            4..=6 => registers[(operand - 4) as usize],
+            _ => panic!("Reserved value"),
+        };
+        let [a, b, c] = registers;
+
+        match op {
+            OpCode::Adv => *a /= u64::pow(2, combo as u32),
+            OpCode::Bxl => *b ^= operand as u64,
+            OpCode::Bst => *b = combo % 8,
+            OpCode::Jnz if *a != 0 => return Flow::Jump(operand as usize),
+            OpCode::Jnz => {}
+            OpCode::Bxc => *b ^= *c,
+            OpCode::Out => device.output.push_back((combo % 8) as u8),
+            OpCode::Bdv => *b = *a / u64::pow(2, combo as u32),
+            OpCode::Cdv => *c = *a / u64::pow(2, combo as u32),
+        }
+        Flow::Next
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Device(Machine<ThreeBit>);
+
+impl Deref for Device {
+    type Target = Machine<ThreeBit>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Device {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromStr for Device {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let mut register = |name: &str| {
+            cursor.skip_whitespace();
+            cursor.tag(&format!("Register {}: ", name))?;
+            cursor.integer()
+        };
+        let a = register("A")?;
+        let b = register("B")?;
+        let c = register("C")?;
+
+        cursor.skip_whitespace();
+        cursor.tag("Program: ")?;
+        // Every word is 3 bits, and a combo operand cannot be the reserved 7
+        let mut opcode = None;
+        let memory = cursor.separated(',', |cursor| {
+            let start = *cursor;
+            let word: u8 = cursor.integer()?;
+            match (opcode.take().map(|op: OpCode| op.operand()), word) {
+                (None, 0..=7) => opcode = Some(OpCode::from(word)),
+                (None, _) => return Err(start.error(format!("Invalid opcode {}", word))),
+                (Some(Operand::Combo), 7) => return Err(start.error("Reserved combo operand 7")),
+                (Some(_), 0..=7) => {}
+                (Some(_), _) => return Err(start.error(format!("Invalid operand {}", word))),
+            }
+            Ok(word)
+        })?;
+        cursor.end()?;
+
+        Ok(Self::new([a, b, c], memory))
+    }
+}
+
+impl Device {
+    pub fn new(registers: [u64; 3], memory: Vec<u8>) -> Self {
+        Self(Machine::new(memory, registers))
+    }
+
+    pub fn run(&mut self) -> String {
+        self.0.run();
+        self.output()
+    }
+
+    /// Runs until the end, recording every step.
+    pub fn trace(&mut self) -> Vec<TraceStep> {
+        let mut steps = vec![];
+        while let Some(step) = self.trace_step() {
+            steps.push(step);
+        }
+        steps
+    }
+
+    /// Runs a single step, telling what it did.
+    pub fn trace_step(&mut self) -> Option<TraceStep> {
+        let ip = self.ip;
+        let before = self.registers;
+        let outputs = self.output.len();
+        if !self.step() {
+            return None;
+        }
+
+        Some(TraceStep {
+            ip,
+            instruction: instruction(&self.program[ip..ip + 2]),
+            before,
+            after: self.registers,
+            output: self.output.get(outputs).copied(),
+        })
+    }
+
+    pub fn ended(&self) -> bool {
+        self.halted || ThreeBit::decode(&self.program, self.ip).is_none()
+    }
+
+    pub fn output(&self) -> String {
+        self.output.iter().join(",")
+    }
+}
+
+/// What a step of the device did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    ip: usize,
+    instruction: String,
+    before: [u64; 3],
+    after: [u64; 3],
+    pub output: Option<u8>,
+}
+
+impl TraceStep {
+    // Registers changed by the step, with their old and new value
+    pub fn changes(&self) -> Vec<(char, u64, u64)> {
+        (0..3)
+            .filter(|&r| self.before[r] != self.after[r])
+            .map(|r| (REGISTERS[r], self.before[r], self.after[r]))
+            .collect()
+    }
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = format!("{:>3}: {:<8}", self.ip, self.instruction);
+        for (register, before, after) in self.changes() {
+            line += &format!(" {}: {} -> {}", register, before, after);
+        }
+        if let Some(output) = self.output {
+            line += &format!(" out {}", output);
+        }
+        write!(f, "{}", line.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn can_parse_device() {
+        let input = "Register A: 729
+Register B: 82341
+Register C: 432
+
+Program: 0,1,5,4,3,0";
+
+        let expected = Device::new([729, 82341, 432], vec![0, 1, 5, 4, 3, 0]);
+
+        assert_eq!(Ok(expected), input.parse());
+    }
+
+    #[rstest]
+    #[case("0,1,8,4", (5, 14))]
+    #[case("0,1,5,9", (5, 16))]
+    #[case("0,7,5,4", (5, 12))]
+    fn rejects_invalid_program(#[case] program: &str, #[case] position: (usize, usize)) {
+        let input = format!(
+            "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {}",
+            program
+        );
+        let error = input.parse::<Device>().unwrap_err();
+
+        assert_eq!(position, (error.line, error.column));
+    }
+
+    #[rstest]
+    #[case(10, 0, 10)]
+    #[case(10, 1, 5)]
+    #[case(40, 2, 10)]
+    #[case(40, 3, 5)]
+    #[case(4, 4, 0)]
+    #[case(32, 5, 4)]
+    #[case(32, 6, 1)]
+    fn test_division(#[case] numerator: u64, #[case] operand: u8, #[case] result: u64) {
+        let mut device = Device::new([numerator, 3, 5], vec![OpCode::Adv as u8, operand]);
+
+        device.step();
+
+        assert_eq!(result, device.registers[0]);
+        assert_eq!(2, device.ip);
+    }
+
+    #[test]
+    fn test_bxl() {
+        let mut device = Device::new([0, 7435, 5], vec![OpCode::Bxl as u8, 6]);
+
+        device.step();
+
+        assert_eq!(device.registers[1], 7437);
+        assert_eq!(device.ip, 2);
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(2, 2)]
+    #[case(3, 3)]
+    #[case(4, 7)]
+    #[case(5, 3)]
+    #[case(6, 2)]
+    fn test_bst(#[case] operand: u8, #[case] expected: u8) {
+        let mut device = Device::new([31, 67, 10], vec![OpCode::Bst as u8, operand]);
+
+        device.step();
+
+        assert_eq!(device.registers[1], expected as u64);
+        assert_eq!(device.ip, 2);
+    }
+
+    #[rstest]
+    #[case(0, 2, 2)]
+    #[case(1, 2, 2)]
+    #[case(2, 3, 3)]
+    #[case(6, 5, 5)]
+    fn test_jnz(#[case] a: u64, #[case] operand: u8, #[case] expected: usize) {
+        let mut device = Device::new([a, 0, 0], vec![OpCode::Jnz as u8, operand]);
+
+        device.step();
+
+        assert_eq!(device.ip, expected);
+    }
+
+    #[rstest]
+    #[case(10, 5, 15)]
+    #[case(34, 12, 46)]
+    #[case(68, 543, 603)]
+    fn test_bxc(#[case] b: u64, #[case] c: u64, #[case] expected: u64) {
+        let mut device = Device::new([0, b, c], vec![OpCode::Bxc as u8, 0]);
+
+        device.step();
+
+        assert_eq!(expected, device.registers[1]);
+        assert_eq!(device.ip, 2);
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(2, 2)]
+    #[case(3, 3)]
+    #[case(4, 4)]
+    #[case(5, 0)]
+    #[case(6, 2)]
+    fn test_out(#[case] operand: u8, #[case] expected: u8) {
+        let mut device = Device::new([292, 184, 8234], vec![OpCode::Out as u8, operand]);
+
+        device.step();
+
+        assert_eq!(expected, device.output[0]);
+        assert_eq!(device.ip, 2);
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut device: Device = fs::read_to_string("examples/17_1.txt")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let trace = device.trace();
+
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", device.output());
+        assert_eq!(30, trace.len());
+        assert_eq!("  0: adv 1    a: 729 -> 364", trace[0].to_string());
+        assert_eq!("  2: out a    out 4", trace[1].to_string());
+        assert_eq!("  4: jnz 0", trace[2].to_string());
+        assert_eq!(Some(0), trace[28].output);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{env, fs};
+
+use advent_of_code::error::{AocError, AocResult};
+
+mod assembler;
+mod debugger;
+mod device;
+
+use assembler::assemble;
+use debugger::Debugger;
+use device::{disassemble, program, Device, OpCode};
+
+fn part_one(input: &str) -> AocResult<String> {
+    let mut device: Device = input.parse()?;
+    Ok(device.run())
+}
+
+fn part_two(input: &str) -> AocResult<u64> {
+    let initial: Device = input.parse()?;
+
+    // The program must output itself
+    solve_inverse(&initial, &initial.program).ok_or(AocError::NoSolution)
+}
+
+/// Bits of a register when only some of them are known, the others could be anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bits {
+    known: u64,
+    // Always 0 where the bit is not known
+    value: u64,
+}
+
+impl Bits {
+    fn constant(value: u64) -> Self {
+        Self {
+            known: u64::MAX,
+            value,
+        }
+    }
+
+    fn is_known(&self) -> bool {
+        self.known == u64::MAX
+    }
+
+    fn shr(self, shift: Bits) -> Self {
+        if !shift.is_known() {
+            return Self { known: 0, value: 0 };
+        }
+        if shift.value >= 64 {
+            return Self::constant(0);
+        }
+        // The bits coming in at the top are zeros
+        Self {
+            known: (self.known >> shift.value) | !(u64::MAX >> shift.value),
+            value: self.value >> shift.value,
+        }
+    }
+
+    fn xor(self, other: Bits) -> Self {
+        let known = self.known & other.known;
+        Self {
+            known,
+            value: (self.value ^ other.value) & known,
+        }
+    }
+
+    fn low3(self) -> Self {
+        Self {
+            known: self.known | !7,
+            value: self.value & 7,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Mismatch,
+    // Depends on the bits not known yet
+    Undecided,
+    Matches,
+}
+
+// Gives up on programs running longer than that
+const MAX_SYMBOLIC_STEPS: usize = 100_000;
+
+// Runs the program with only some bits of A known. The jumps must be decided by the
+// known bits, and every value known for sure is compared to the target as soon as
+// it is output.
+fn run_symbolic(device: &Device, a: Bits, target: &[u8]) -> Outcome {
+    let memory = &device.program;
+    let [_, b, c] = device.registers;
+    let mut registers = [a, Bits::constant(b), Bits::constant(c)];
+    let mut ip = 0;
+    let mut outputs = 0;
+    let mut all_known = true;
+
+    for _ in 0..MAX_SYMBOLIC_STEPS {
+        if ip + 1 >= memory.len() {
+            return match (outputs == target.len(), all_known) {
+                (false, _) => Outcome::Mismatch,
+                (true, true) => Outcome::Matches,
+                (true, false) => Outcome::Undecided,
+            };
+        }
+
+        let operand = memory[ip + 1];
+        let combo = match operand {
+            0..=3 => Bits::constant(operand as u64),
+            4..=6 => registers[(operand - 4) as usize],
+            _ => return Outcome::Mismatch,
+        };
+
+        match OpCode::from(memory[ip]) {
+            OpCode::Adv => registers[0] = registers[0].shr(combo),
+            OpCode::Bxl => registers[1] = registers[1].xor(Bits::constant(operand as u64)),
+            OpCode::Bst => registers[1] = combo.low3(),
+            OpCode::Jnz => {
+                if registers[0].value != 0 {
+                    ip = operand as usize;
+                    continue;
+                } else if !registers[0].is_known() {
+                    return Outcome::Undecided;
+                }
+            }
+            OpCode::Bxc => registers[1] = registers[1].xor(registers[2]),
+            OpCode::Out => {
+                let output = combo.low3();
+                if outputs == target.len()
+                    || (output.value ^ target[outputs] as u64) & output.known != 0
+                {
+                    return Outcome::Mismatch;
+                }
+                all_known &= output.is_known();
+                outputs += 1;
+            }
+            OpCode::Bdv => registers[1] = registers[0].shr(combo),
+            OpCode::Cdv => registers[2] = registers[0].shr(combo),
+        }
+        ip += 2;
+    }
+
+    Outcome::Undecided
+}
+
+/// Smallest initial value of register A for the device to output `target`, for
+/// any program. The bits of A are chosen from the lowest, leaving the others
+/// unknown while running the program, to give up on a choice as soon as an output
+/// is known to be wrong.
+fn solve_inverse(device: &Device, target: &[u8]) -> Option<u64> {
+    fn search(device: &Device, target: &[u8], bits: u32, value: u64, best: &mut Option<u64>) {
+        // Every value with these low bits is at least as big
+        if best.is_some_and(|best| value >= best) {
+            return;
+        }
+
+        let known = u64::MAX.checked_shr(64 - bits).unwrap_or(0);
+        match run_symbolic(device, Bits { known, value }, target) {
+            // Whatever the other bits, leaving them to 0 is the smallest
+            Outcome::Matches => *best = Some(value),
+            Outcome::Undecided if bits < 64 => {
+                search(device, target, bits + 1, value, best);
+                search(device, target, bits + 1, value | (1 << bits), best);
+            }
+            _ => {}
+        }
+    }
+
+    let mut best = None;
+    search(device, target, 0, 0, &mut best);
+    best
+}
+
+fn main() {
+    // `--disassemble` lists the program of the input, `--assemble <file>` gives the
+    // program line of a listing, e.g. to write a custom input
+    let args: Vec<String> = env::args().collect();
+    if let Some(idx) = args.iter().position(|arg| arg == "--assemble") {
+        let Some(path) = args.get(idx + 1) else {
+            println!("Usage: --assemble <file>");
+            return;
+        };
+        match fs::read_to_string(path).map(|source| assemble(&source)) {
+            Ok(Ok(memory)) => println!("{}", program(&memory)),
+            Ok(Err(e)) => println!("Invalid program at {}", e),
+            Err(e) => println!("Cannot read {}: {}", path, e),
+        }
+        return;
+    }
+
+    // `--trace` prints every step of part one, `--debug` runs it in a debugger
+    let input = fs::read_to_string("inputs/17.txt").unwrap();
+    let tools = ["--disassemble", "--trace", "--debug"];
+    if let Some(tool) = args.iter().find(|arg| tools.contains(&arg.as_str())) {
+        match (tool.as_str(), input.parse::<Device>()) {
+            ("--disassemble", Ok(device)) => println!("{}", disassemble(&device.program)),
+            ("--trace", Ok(mut device)) => device.trace().iter().for_each(|s| println!("{}", s)),
+            (_, Ok(device)) => Debugger::new(device).repl(),
+            (_, Err(e)) => println!("Invalid input at {}", e),
+        }
+        return;
+    }
+
+    advent_of_code::solve(1, &input, part_one);
+    advent_of_code::solve(2, &input, part_two);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn part_one_test() {
+        let input = fs::read_to_string("examples/17_1.txt").unwrap();
+        assert_eq!(Ok(String::from("4,6,3,5,6,3,5,2,1,0")), part_one(&input));
+    }
+
+    #[rstest]
+    #[case("adv 3\nout a\njnz 0", 117440)]
+    #[case("bst a\nbxl 5\ncdv b\nbxl 6\nbxc\nout b\nadv 3\njnz 0", 37221261688308)]
+    #[case("adv 1\nbst a\nbxl 3\nout b\njnz 0", 931)]
+    #[case("cdv 2\nbxc\nout b\nbdv 1\nbxl 4\nadv 2\nout b\njnz 0", 75023)]
+    fn test_solve_inverse(#[case] source: &str, #[case] a: u64) {
+        let device = |a| Device::new([a, 0, 0], assemble(source).unwrap());
+        let output = |a| {
+            let mut device = device(a);
+            device.run();
+            Vec::from(device.output.clone())
+        };
+
+        let target = output(a);
+        let found = solve_inverse(&device(0), &target).unwrap();
+        assert_eq!(target, output(found));
+
+        // Nothing smaller gives the same output
+        let smallest = (0..=a.min(20_000)).find(|&a| output(a) == target);
+        assert!(found <= a);
+        assert!(smallest.is_none_or(|smallest| smallest == found));
+    }
+
+    #[test]
+    fn part_two_test() {
+        let input = fs::read_to_string("examples/17_2.txt").unwrap();
+        assert_eq!(Ok(117440), part_two(&input));
+    }
+}
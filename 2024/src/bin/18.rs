@@ -1,121 +1,91 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashSet, VecDeque},
-    fs,
-};
+use std::{fs, num::NonZeroUsize};
 
 use advent_of_code::{
-    coordinate::{self, Coordinate},
+    coordinate::Coordinate,
+    error::{AocError, AocResult},
     grid::Grid,
+    params::Params,
+    search::bfs,
 };
 
-fn read_coordinates(input: &str) -> Vec<Coordinate> {
-    input
-        .lines()
-        .map(|l| {
-            let (xs, ys) = l.split_once(",").unwrap();
-            let y = ys.parse().unwrap();
-            let x = xs.parse().unwrap();
-            Coordinate::new(y, x)
-        })
-        .collect()
-}
+/// Puzzle parameters, which the examples shrink.
+const PARAMS: &[&str] = &["height", "width", "steps"];
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct State {
-    coordinate: Coordinate,
-    cost: u32,
-}
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+fn read_coordinates(input: &str) -> AocResult<Vec<Coordinate>> {
+    Ok(input.lines().map(str::parse).collect::<Result<_, _>>()?)
 }
 
-fn dijkstra(grid: &Grid<bool>, start: Coordinate, end: Coordinate) -> Option<u32> {
-    let mut costs = Grid::<u32>::initialize(grid.height, grid.width, u32::MAX);
-    costs.set(start, 0);
-
-    let mut heap = BinaryHeap::from([State {
-        coordinate: start,
-        cost: 0,
-    }]);
-
-    while let Some(state) = heap.pop() {
-        let neighbours: Vec<State> = state
-            .coordinate
-            .around()
-            .iter()
-            .filter(|d| grid.get(&d).is_some_and(|v| *v))
-            .map(|d| State {
-                coordinate: *d,
-                cost: state.cost + 1,
-            })
-            .collect();
-
-        for next in neighbours {
-            let min_cost = *costs.get(&next.coordinate).unwrap();
-
-            if next.cost < min_cost {
-                costs.set(next.coordinate, next.cost);
-                heap.push(next);
-            }
-        }
-    }
-
-    let exit = *costs.get(&end).unwrap();
-    if exit == u32::MAX {
-        None
-    } else {
-        Some(exit)
-    }
+fn shortest_path(grid: &Grid<bool>, start: Coordinate, end: Coordinate) -> Option<u32> {
+    bfs(
+        start,
+        |c| {
+            c.around()
+                .into_iter()
+                .filter(|d| grid.get(d).is_some_and(|v| *v))
+        },
+        |c| *c == end,
+    )
+    .map(|result| result.cost as u32)
 }
 
+/// Height and width of the memory space, which cannot be empty.
+fn size(params: &Params) -> AocResult<(usize, usize)> {
+    let default = NonZeroUsize::new(71).unwrap();
+    let height: NonZeroUsize = params.get("height", default)?;
+    let width: NonZeroUsize = params.get("width", default)?;
+    Ok((height.get(), width.get()))
+}
 
-fn part_one(input: &str, height: usize, width: usize, steps: usize) -> Option<u32> {
-    let coordinates = read_coordinates(input);
+fn part_one(input: &str, params: &Params) -> AocResult<u32> {
+    let (height, width) = size(params)?;
+    let steps = params.get("steps", 1024)?;
+    let coordinates = read_coordinates(input)?;
+    if coordinates.len() < steps {
+        return Err(AocError::assumption(format!(
+            "at least {} bytes fall",
+            steps
+        )));
+    }
     let mut grid = Grid::initialize(height, width, true);
-    for i in 0..steps {
-        let coord = coordinates[i];
+    for &coord in coordinates.iter().take(steps) {
         grid.set(coord, false);
     }
 
-    dijkstra(
+    shortest_path(
         &grid,
         Coordinate::new(0, 0),
         Coordinate::new((height - 1) as i32, (width - 1) as i32),
     )
+    .ok_or(AocError::NoSolution)
 }
 
-fn part_two(input: &str, height: usize, width: usize) -> Option<String> {
-    let coordinates = read_coordinates(input);
+fn part_two(input: &str, params: &Params) -> AocResult<String> {
+    let (height, width) = size(params)?;
+    let coordinates = read_coordinates(input)?;
     let mut grid = Grid::initialize(height, width, true);
     for i in 0..coordinates.len() {
         let coord = coordinates[i];
         grid.set(coord, false);
 
-        if dijkstra(
+        if shortest_path(
             &grid,
             Coordinate::new(0, 0),
             Coordinate::new((height - 1) as i32, (width - 1) as i32),
         )
         .is_none()
         {
-            return Some(format!("{},{}", coord.x, coord.y));
+            return Ok(format!("{},{}", coord.x, coord.y));
         }
     }
-    None
+    Err(AocError::NoSolution)
 }
 
 fn main() {
     let input = fs::read_to_string("inputs/18.txt").unwrap();
-    advent_of_code::solve(1, &input, |i| part_one(i, 71, 71, 1024));
-    advent_of_code::solve(2, &input, |i| part_two(i, 71, 71));
+    // An invalid --param fails both parts
+    let params = Params::from_args(PARAMS).map_err(AocError::from);
+    advent_of_code::solve(1, &input, |i| part_one(i, &params.clone()?));
+    advent_of_code::solve(2, &input, |i| part_two(i, &params.clone()?));
 }
 
 #[cfg(test)]
@@ -126,12 +96,30 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/18.txt").unwrap();
-        assert_eq!(Some(22), part_one(&input, 7, 7, 12));
+        let params = Params::new()
+            .with("height", 7)
+            .with("width", 7)
+            .with("steps", 12);
+        assert_eq!(Ok(22), part_one(&input, &params));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/18.txt").unwrap();
-        assert_eq!(Some("6,1".into()), part_two(&input, 7, 7));
+        let params = Params::new().with("height", 7).with("width", 7);
+        assert_eq!(Ok("6,1".into()), part_two(&input, &params));
+    }
+
+    #[test]
+    fn invalid_params_test() {
+        let input = "5,4\n4,2\n";
+        assert!(matches!(
+            part_one(input, &Params::new().with("height", 7).with("width", 7)),
+            Err(AocError::Assumption(_))
+        ));
+        assert!(matches!(
+            part_two(input, &Params::new().with("height", 0)),
+            Err(AocError::Param(_))
+        ));
     }
 }
@@ -1,8 +1,11 @@
 use std::{
     fmt::{self, Display, Formatter},
     ops::{Add, Div, Mul, Sub},
+    str::FromStr,
 };
 
+use crate::parse::{Cursor, ParseError};
+
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub struct Coordinate {
     pub y: i32,
@@ -40,6 +43,21 @@ impl Display for Coordinate {
     }
 }
 
+// Inputs give coordinates as `x,y`
+impl FromStr for Coordinate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let x = cursor.integer()?;
+        cursor.char(',')?;
+        cursor.skip_spaces();
+        let y = cursor.integer()?;
+        cursor.end()?;
+        Ok(Self::new(y, x))
+    }
+}
+
 impl Add<Coordinate> for Coordinate {
     type Output = Coordinate;
 
@@ -3,7 +3,10 @@ use std::{
     str::FromStr,
 };
 
-use crate::coordinate::Coordinate;
+use crate::{
+    coordinate::Coordinate,
+    parse::{Cursor, ParseResult},
+};
 
 #[derive(Clone, Debug)]
 pub struct Grid<T> {
@@ -113,6 +116,26 @@ impl Grid<char> {
         }
     }
 
+    /// Same as [Grid::new_chars], failing on a character not in `allowed` or on
+    /// lines of different lengths.
+    pub fn parse_chars(input: &str, allowed: &str) -> ParseResult<Self> {
+        let mut cursor = Cursor::new(input);
+        let mut width = None;
+        while !cursor.is_empty() {
+            let line = cursor.take_while(|c| allowed.contains(c));
+            match cursor.peek() {
+                None | Some('\n' | '\r') => {}
+                Some(c) => return Err(cursor.error(format!("Unexpected {:?}", c))),
+            }
+            let width = *width.get_or_insert(line.len());
+            if line.len() != width {
+                return Err(cursor.error(format!("Expected lines of {} characters", width)));
+            }
+            cursor.line();
+        }
+        Ok(Self::new_chars(input))
+    }
+
     pub fn find_first(&self, arg: char) -> Option<Coordinate> {
         for y in 0..self.height {
             for x in 0..self.width {
@@ -1,14 +1,20 @@
 use std::{fmt::Display, time::Instant};
 
+use error::Answer;
+
 pub mod coordinate;
 pub mod grid;
 
-pub fn solve<T: Display>(part: usize, input: &str, solve_fn: impl Fn(&str) -> Option<T>) {
+pub use aoc_common::{cycle, dag, error, graph, math, params, parse, search, vm};
+
+/// Runs a solution and reports its answer, or why it failed.
+pub fn solve<T: Display, A: Answer<T>>(part: usize, input: &str, solve_fn: impl Fn(&str) -> A) {
     let start = Instant::now();
-    let result = solve_fn(input);
+    let result = solve_fn(input).into_result();
     let duration = start.elapsed();
 
-    if let Some(res) = result {
-        println!("🎄 Part {} solution: {} (took {:?})", part, res, duration);
+    match result {
+        Ok(res) => println!("🎄 Part {} solution: {} (took {:?})", part, res, duration),
+        Err(e) => println!("🎄 Part {} failed: {} (took {:?})", part, e, duration),
     }
 }
@@ -1,47 +1,37 @@
 use std::fs;
 
-use advent_of_code::{coordinate::Coordinate, grid::Grid};
+use advent_of_code::{
+    coordinate::Coordinate,
+    error::AocResult,
+    grid::Grid,
+    simulation::{Simulation, Update},
+};
 
-fn part_one(input: &str) -> Option<u32> {
-    let grid = Grid::new_chars(input);
-    Some(accessible(&grid).iter().count() as u32)
-}
-
-fn part_two(input: &str) -> Option<u32> {
-    let mut grid = Grid::new_chars(input);
-
-    let mut count = 0;
-    let mut coords = accessible(&grid);
-    while coords.iter().count() > 0 {
-        count += coords.iter().count();
-        coords.iter().for_each(|c| grid.set(c, '.'));
-        coords = accessible(&grid);
+// Rolls with less than 4 rolls around are taken out at each step
+fn remove_accessible(grid: &Grid<char>, coord: &Coordinate, value: &char) -> char {
+    if *value != '@' {
+        return *value;
     }
 
-    Some(count as u32)
-}
+    let rolls_around = coord
+        .around_all()
+        .iter()
+        .filter(|c| grid.get(c) == Some(&'@'))
+        .count();
 
-fn accessible(grid: &Grid<char>) -> Vec<Coordinate> {
-    let mut coordinates = vec![];
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            let coord = Coordinate::new(y as i32, x as i32);
-            if grid.get(&coord) != Some(&'@') {
-                continue;
-            }
+    if rolls_around < 4 { '.' } else { '@' }
+}
 
-            let rolls_around = coord
-                .around_all()
-                .iter()
-                .filter(|c| grid.get(c) == Some(&'@'))
-                .count();
+fn part_one(input: &str) -> AocResult<u32> {
+    let grid = Grid::parse_chars(input, ".@")?;
+    let mut simulation = Simulation::new(grid, Update::Synchronous, remove_accessible);
+    Ok(simulation.step() as u32)
+}
 
-            if rolls_around < 4 {
-                coordinates.push(coord);
-            }
-        }
-    }
-    coordinates
+fn part_two(input: &str) -> AocResult<u32> {
+    let grid = Grid::parse_chars(input, ".@")?;
+    let mut simulation = Simulation::new(grid, Update::Synchronous, remove_accessible);
+    Ok(simulation.run_until_stable().iter().sum::<usize>() as u32)
 }
 
 fn main() {
@@ -58,12 +48,19 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/04.txt").unwrap();
-        assert_eq!(Some(13), part_one(&input));
+        assert_eq!(Ok(13), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/04.txt").unwrap();
-        assert_eq!(Some(43), part_two(&input));
+        assert_eq!(Ok(43), part_two(&input));
+    }
+
+    #[test]
+    fn invalid_test() {
+        assert!(part_one("@@.\n@#@\n").is_err());
+        assert!(part_one("@@.\n@@\n").is_err());
+        assert_eq!(Ok(3), part_one("@@\r\n@.\r\n"));
     }
 }
@@ -1,114 +1,63 @@
 use std::fs;
 
-#[derive(Clone, Copy, Debug)]
-struct Range {
-    low: u64,
-    high: u64,
-}
-
-impl Range {
-    pub fn size(&self) -> u64 {
-        self.high - self.low + 1
-    }
-
-    pub fn contains(&self, n: u64) -> bool {
-        n >= self.low && n <= self.high
-    }
-
-    pub fn overlap(&self, other: &Range) -> bool {
-        self.low <= other.high && other.low <= self.high
-    }
-
-    pub fn merge(&self, other: &Range) -> Range {
-        Range {
-            low: if self.low < other.low {
-                self.low
-            } else {
-                other.low
-            },
-            high: if self.high > other.high {
-                self.high
-            } else {
-                other.high
-            },
-        }
+use advent_of_code::{
+    error::AocResult,
+    interval::{Interval, IntervalSet},
+    parse::{Cursor, ParseResult},
+};
+
+// Nothing but spaces may follow on the line
+fn end_of_line(cursor: &mut Cursor) -> ParseResult<()> {
+    let end = *cursor;
+    if cursor.line().trim().is_empty() {
+        Ok(())
+    } else {
+        Err(end.error("Expected the end of the line"))
     }
 }
 
-fn parse(input: &str) -> (Vec<Range>, Vec<u64>) {
-    let mut is_range = true;
+// The fresh ranges like `3-5`, a blank line, then the available ingredients
+fn parse(input: &str) -> ParseResult<(IntervalSet<u64>, Vec<u64>)> {
+    let mut cursor = Cursor::new(input);
+
     let mut ranges = vec![];
-    let mut ingredients = vec![];
-    for line in input.lines() {
-        if line.is_empty() {
-            is_range = false;
-            continue;
+    while !matches!(cursor.peek(), Some('\n' | '\r')) {
+        if cursor.is_empty() {
+            return Err(cursor.error("Expected a blank line before the ingredients"));
         }
-
-        if is_range {
-            let (start, end) = line.split_once("-").unwrap();
-            ranges.push(Range {
-                low: start.parse().unwrap(),
-                high: end.parse().unwrap(),
-            });
-        } else {
-            ingredients.push(line.parse().unwrap());
+        let start = cursor;
+        let low: u64 = cursor.integer()?;
+        cursor.char('-')?;
+        let high: u64 = cursor.integer()?;
+        match high.checked_add(1) {
+            Some(end) if low <= high => ranges.push(Interval::new(low, end)),
+            _ => return Err(start.error(format!("Invalid range {}-{}", low, high))),
         }
+        end_of_line(&mut cursor)?;
     }
 
-    (ranges, ingredients)
-}
-
-fn part_one(input: &str) -> Option<u32> {
-    let (ranges, ingredients) = parse(input);
+    let mut ingredients = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        ingredients.push(cursor.integer()?);
+        end_of_line(&mut cursor)?;
+        cursor.skip_whitespace();
+    }
 
-    Some(
-        ingredients
-            .iter()
-            .filter(|i| ranges.iter().any(|r| r.contains(**i)))
-            .count() as u32,
-    )
+    Ok((ranges.into_iter().collect(), ingredients))
 }
 
-fn merge_ranges(ranges: &Vec<Range>) -> Vec<Range> {
-    // Assume the vec is sorted by Range.low
-    let mut res = vec![];
-
-    let mut prev = ranges[0];
-    for i in 1..ranges.len() {
-        let curr = ranges[i];
-        if prev.overlap(&curr) {
-            prev = prev.merge(&curr);
-        } else {
-            res.push(prev);
-            prev = curr;
-        }
-    }
-    res.push(prev);
+fn part_one(input: &str) -> AocResult<u32> {
+    let (ranges, ingredients) = parse(input)?;
 
-    res.sort_by_key(|r| r.low);
-    res
+    Ok(ingredients.iter().filter(|i| ranges.contains(**i)).count() as u32)
 }
 
-fn part_two(input: &str) -> Option<u64> {
-    let mut ranges = parse(input).0;
-    ranges.sort_by_key(|r| r.low);
-    let mut prev_size = ranges.len();
+fn part_two(input: &str) -> AocResult<u64> {
+    let (ranges, _) = parse(input)?;
 
-    ranges = merge_ranges(&ranges);
-    while ranges.len() != prev_size {
-        prev_size = ranges.len();
-        ranges = merge_ranges(&ranges);
-    }
-
-    Some(
-        ranges
-            .iter()
-        .map(|r| {
-                r.size()
-            })
-            .sum::<u64>() as u64,
-    )
+    // Overlapping ranges are merged by the set
+    Ok(ranges.len())
 }
 
 fn main() {
@@ -125,12 +74,27 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/05.txt").unwrap();
-        assert_eq!(Some(3), part_one(&input));
+        assert_eq!(Ok(3), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/05.txt").unwrap();
-        assert_eq!(Some(14), part_two(&input));
+        assert_eq!(Ok(14), part_two(&input));
+    }
+
+    #[test]
+    fn parse_test() {
+        let (ranges, ingredients) = parse("3-5\r\n10-14\r\n\r\n1\r\n5\r\n").unwrap();
+        assert_eq!((8, vec![1, 5]), (ranges.len(), ingredients));
+
+        let error = parse("3-5\n10=14\n\n1\n").unwrap_err();
+        assert_eq!((2, 3), (error.line, error.column));
+        let error = parse("3-5\n14-10\n\n1\n").unwrap_err();
+        assert_eq!((2, 1), (error.line, error.column));
+        let error = parse("3-5\n\n1\nx\n").unwrap_err();
+        assert_eq!((4, 1), (error.line, error.column));
+        let error = parse("3-5\n").unwrap_err();
+        assert_eq!((2, 1), (error.line, error.column));
     }
 }
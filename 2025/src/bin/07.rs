@@ -1,47 +1,51 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
+use std::fs;
+
+use advent_of_code::{
+    coordinate::{Coordinate, EAST, NORTH, WEST},
+    error::AocResult,
+    grid::Grid,
+    simulation::{Simulation, Update},
 };
 
-use advent_of_code::{coordinate::Coordinate, grid::Grid};
+fn has_beam(grid: &Grid<char>, coord: &Coordinate) -> bool {
+    matches!(grid.get(coord), Some('S') | Some('|'))
+}
 
-fn part_one(input: &str) -> Option<u32> {
-    let grid = Grid::new_chars(input);
+// Updating row by row, the beams reach the bottom in a single step
+fn propagate(grid: &Grid<char>, coord: &Coordinate, value: &char) -> char {
+    let split_into = |side: Coordinate| {
+        grid.get(&(*coord + side)) == Some(&'^') && has_beam(grid, &(*coord + side + NORTH))
+    };
+    if *value == '.' && (has_beam(grid, &(*coord + NORTH)) || split_into(WEST) || split_into(EAST))
+    {
+        '|'
+    } else {
+        *value
+    }
+}
 
-    let mut count = 0;
-    let mut front = vec![false; grid.width];
+// The manifold, with its beam source and splitters
+fn read_grid(input: &str) -> AocResult<Grid<char>> {
+    Ok(Grid::parse_chars(input, ".S^")?)
+}
 
-    front[grid.find_first('S').unwrap().x as usize] = true;
+fn part_one(input: &str) -> AocResult<u32> {
+    let grid = read_grid(input)?;
 
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            if front[x] {
-                let value = *grid
-                    .get(&Coordinate {
-                        y: y as i32,
-                        x: x as i32,
-                    })
-                    .unwrap();
-                if value == '^' {
-                    count += 1;
-                    if x > 0 {
-                        front[x - 1] = true;
-                    }
-                    if x < grid.width - 1 {
-                        front[x + 1] = true;
-                    }
-                    front[x] = false;
-                }
-            }
-        }
-    }
+    let mut simulation = Simulation::new(grid, Update::Asynchronous, propagate);
+    simulation.run_until_stable();
 
-    Some(count)
-}
+    let grid = simulation.grid;
+    let count = grid
+        .coordinates()
+        .filter(|c| grid.get(c) == Some(&'^') && has_beam(&grid, &(*c + NORTH)))
+        .count();
 
+    Ok(count as u32)
+}
 
-fn part_two(input: &str) -> Option<u64> {
-    let grid = Grid::new_chars(input);
+fn part_two(input: &str) -> AocResult<u64> {
+    let grid = read_grid(input)?;
 
     let mut front = vec![0; grid.width];
 
@@ -68,7 +72,7 @@ fn part_two(input: &str) -> Option<u64> {
         }
     }
 
-    Some(front.iter().sum())
+    Ok(front.iter().sum())
 }
 
 fn main() {
@@ -85,12 +89,19 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/07.txt").unwrap();
-        assert_eq!(Some(21), part_one(&input));
+        assert_eq!(Ok(21), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/07.txt").unwrap();
-        assert_eq!(Some(40), part_two(&input));
+        assert_eq!(Ok(40), part_two(&input));
+    }
+
+    #[test]
+    fn invalid_test() {
+        assert!(part_one("..S..\n..|..\n").is_err());
+        assert!(part_two("..S..\n.^.\n").is_err());
+        assert_eq!(Ok(2), part_two(".S.\r\n.^.\r\n"));
     }
 }
@@ -3,21 +3,37 @@ use std::{
     fs::{self},
 };
 
-use advent_of_code::coordinate::Coordinate;
-
-fn parse(input: &str) -> Vec<Coordinate> {
-    input
-        .lines()
-        .map(|l| {
-            let split = l.split_once(',').unwrap();
-            Coordinate::new(
-                split.1.parse::<i32>().unwrap(),
-                split.0.parse::<i32>().unwrap(),
-            )
-        })
-        .collect()
+use advent_of_code::{
+    assumptions::{self, Assumption},
+    coordinate::Coordinate,
+    error::{AocError, AocResult},
+    parse::{Cursor, ParseResult},
+};
+
+fn parse(input: &str) -> ParseResult<Vec<Coordinate>> {
+    let mut coords = vec![];
+    let mut cursor = Cursor::new(input);
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let x: i32 = cursor.integer()?;
+        cursor.char(',')?;
+        let y: i32 = cursor.integer()?;
+        coords.push(Coordinate::new(y, x));
+        cursor.skip_whitespace();
+    }
+    Ok(coords)
 }
 
+const ASSUMPTIONS: [Assumption<[Coordinate]>; 1] = [Assumption::new(
+    "consecutive red tiles share a row or a column",
+    |coords| {
+        (0..coords.len()).all(|i| {
+            let (a, b) = (coords[i], coords[(i + 1) % coords.len()]);
+            a.x == b.x || a.y == b.y
+        })
+    },
+)];
+
 struct Rectangle {
     a: Coordinate,
     b: Coordinate,
@@ -34,7 +50,7 @@ impl Rectangle {
         let up = cmp::min(self.a.y, self.b.y);
         let down = cmp::max(self.a.y, self.b.y);
 
-        // Assumption: the lines can be only vertical or horizontal
+        // The lines are only vertical or horizontal, see `ASSUMPTIONS`
         let outside = (la.x <= left && lb.x <= left)
             || (la.x >= right && lb.x >= right)
             || (la.y <= up && lb.y <= up)
@@ -53,25 +69,28 @@ fn rectangles(coords: &[Coordinate]) -> impl Iterator<Item = Rectangle> + '_ {
     })
 }
 
-fn part_one(input: &str) -> Option<u64> {
-    let coords = parse(input);
+fn part_one(input: &str) -> AocResult<u64> {
+    let coords = parse(input)?;
 
-    rectangles(&coords).map(|rect| rect.area()).max()
+    rectangles(&coords)
+        .map(|rect| rect.area())
+        .max()
+        .ok_or(AocError::NoSolution)
 }
 
-fn part_two(input: &str) -> Option<u64> {
-    let coords = parse(input);
+fn part_two(input: &str) -> AocResult<u64> {
+    let coords = parse(input)?;
+    assumptions::check(&coords[..], &ASSUMPTIONS)?;
 
-    let mut lines = vec![];
-    for i in 0..(coords.len() - 1) {
-        lines.push((coords[i], coords[i + 1]));
-    }
-    lines.push((coords[coords.len() - 1], coords[0]));
+    let lines: Vec<_> = (0..coords.len())
+        .map(|i| (coords[i], coords[(i + 1) % coords.len()]))
+        .collect();
 
     rectangles(&coords)
         .filter(|rect| lines.iter().all(|l| !rect.contains(&l.0, &l.1)))
         .map(|rect| rect.area())
         .max()
+        .ok_or(AocError::NoSolution)
 }
 
 fn main() {
@@ -88,12 +107,21 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/09.txt").unwrap();
-        assert_eq!(Some(50), part_one(&input));
+        assert_eq!(Ok(50), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/09.txt").unwrap();
-        assert_eq!(Some(24), part_two(&input));
+        assert_eq!(Ok(24), part_two(&input));
+    }
+
+    #[test]
+    fn part_two_diagonal_test() {
+        let input = "1,1\n5,1\n3,4\n";
+        assert_eq!(
+            vec!["consecutive red tiles share a row or a column"],
+            assumptions::violated(&parse(input).unwrap()[..], &ASSUMPTIONS)
+        );
     }
 }
@@ -1,133 +1,106 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
-};
+use std::{fs, str::FromStr};
 
-use microlp::{LinearExpr, Problem};
+use advent_of_code::{
+    error::{AocError, AocResult},
+    gf2::{BitVector, Matrix},
+    ilp,
+    parse::{Cursor, ParseError},
+};
 
 #[derive(Debug)]
 struct Machine {
-    goal: u16,
+    goal: BitVector,
     buttons: Vec<Vec<u8>>,
     requirements: Vec<u16>,
 }
 
-impl Machine {
-    pub fn from_str(line: &str) -> Self {
-        let splits: Vec<_> = line.split(" ").collect();
-
-        let goal_str = splits[0].as_bytes();
-
-        let mut goal = 0;
-        for i in 0..goal_str.len() {
-            if goal_str[i] == b'#' {
-                let mask = 1 << (i - 1);
-                goal ^= mask;
+// [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+impl FromStr for Machine {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(line);
+
+        let lights = cursor.bracketed('[', ']')?;
+        let goal: BitVector = lights.bytes().map(|light| light == b'#').collect();
+
+        let mut buttons = vec![];
+        cursor.skip_spaces();
+        while cursor.peek() == Some('(') {
+            let start = cursor;
+            let button: Vec<u8> = cursor.list('(', ')', Cursor::integer)?;
+            if let Some(light) = button.iter().find(|&&light| light as usize >= goal.len()) {
+                return Err(start.error(format!(
+                    "Button toggles light {} of {} lights",
+                    light,
+                    goal.len()
+                )));
             }
+            buttons.push(button);
+            cursor.skip_spaces();
         }
 
-        let buttons = splits[1..splits.len() - 1]
-            .iter()
-            .map(|g| {
-                g[1..g.len() - 1]
-                    .to_string()
-                    .split(",")
-                    .map(|n| n.parse().unwrap())
-                    .collect()
-            })
-            .collect();
-
-        let requirements = splits[splits.len() - 1][1..(splits[splits.len() - 1].len() - 1)]
-            .split(",")
-            .map(|n| n.parse().unwrap())
-            .collect();
-
-        Self {
+        let start = cursor;
+        let requirements: Vec<u16> = cursor.list('{', '}', Cursor::integer)?;
+        if requirements.len() != goal.len() {
+            return Err(start.error(format!(
+                "Expected {} joltage requirements, found {}",
+                goal.len(),
+                requirements.len()
+            )));
+        }
+        cursor.end()?;
+
+        Ok(Self {
             goal,
             buttons,
             requirements,
-        }
+        })
     }
 }
 
-fn next_lights(state: u16, button: &Vec<u8>) -> u16 {
-    let mut next = state;
-    for &light in button.iter() {
-        next ^= 1 << light;
-    }
-    next
+fn read_machines(input: &str) -> AocResult<Vec<Machine>> {
+    Ok(input.lines().map(str::parse).collect::<Result<_, _>>()?)
 }
 
-fn solve_part_one(machine: Machine) -> u32 {
-    let mut visited: HashSet<u16> = HashSet::new();
-    let mut queue = VecDeque::new();
-
-    queue.push_back((0, 0));
-
-    while let Some((steps, state)) = queue.pop_front() {
-        if visited.contains(&state) {
-            continue;
-        }
-
-        if state == machine.goal {
-            return steps;
-        }
-
-        visited.insert(state.clone());
-
-        for button in &machine.buttons {
-            queue.push_back((steps + 1, next_lights(state, button)));
-        }
-    }
+// Each button is a column of the lights it toggles, pressing it twice does nothing
+fn solve_part_one(machine: Machine) -> AocResult<u32> {
+    let lights = machine.goal.len();
+    let buttons: Vec<BitVector> = machine
+        .buttons
+        .iter()
+        .map(|button| BitVector::from_indices(lights, button.iter().map(|&l| l as usize)))
+        .collect();
 
-    unreachable!("Did not find a solution")
+    Matrix::from_columns(lights, &buttons)
+        .min_weight_solution(&machine.goal)
+        .map(|presses| presses.weight() as u32)
+        .ok_or(AocError::NoSolution)
 }
 
-fn part_one(input: &str) -> Option<u32> {
-    Some(
-        input
-            .lines()
-            .map(Machine::from_str)
-            .map(solve_part_one)
-            .sum(),
-    )
+fn part_one(input: &str) -> AocResult<u32> {
+    read_machines(input)?.into_iter().map(solve_part_one).sum()
 }
 
-fn solve_part_two(machine: Machine) -> u64 {
-    let mut problem = Problem::new(microlp::OptimizationDirection::Minimize);
-    let variables: Vec<_> = (0..machine.buttons.len())
-        .map(|i| problem.add_integer_var(1.0, (0, i32::MAX)))
+// One equation per counter: the presses of the buttons adding to it
+fn solve_part_two(machine: Machine) -> AocResult<u64> {
+    let matrix: Vec<Vec<i64>> = (0..machine.requirements.len())
+        .map(|counter| {
+            machine
+                .buttons
+                .iter()
+                .map(|button| button.contains(&(counter as u8)) as i64)
+                .collect()
+        })
         .collect();
+    let requirements: Vec<i64> = machine.requirements.iter().map(|&r| r as i64).collect();
 
-    for (req_idx, requirement) in machine.requirements.iter().enumerate() {
-        let mut expr = LinearExpr::empty();
-
-        let mut not_empty = false;
-        for btn_idx in 0..machine.buttons.len() {
-            if machine.buttons[btn_idx].iter().any(|r| (*r as usize) == req_idx) {
-                expr.add(variables[btn_idx], 1.0);
-                not_empty = true;
-            }
-        }
-
-        if not_empty {
-            problem.add_constraint(expr, microlp::ComparisonOp::Eq, (*requirement as i16).into());
-        }
-    }
-
-    let solution = problem.solve().unwrap();
-    
-    solution.objective().round() as u64
+    let presses = ilp::min_sum_solution(&matrix, &requirements).ok_or(AocError::NoSolution)?;
+    Ok(presses.iter().sum::<i64>() as u64)
 }
 
-fn part_two(input: &str) -> Option<u64> {
-    Some(
-        input
-            .lines()
-            .map(Machine::from_str)
-            .map(solve_part_two)
-            .sum(),
-    )
+fn part_two(input: &str) -> AocResult<u64> {
+    read_machines(input)?.into_iter().map(solve_part_two).sum()
 }
 
 fn main() {
@@ -141,15 +114,26 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn parse_test() {
+        let machine: Machine = "[.##.] (3) (1,3) (2) {3,5,4,7}".parse().unwrap();
+        assert_eq!(vec![vec![3], vec![1, 3], vec![2]], machine.buttons);
+
+        let error = "[.##.] (3) (1,4) {3,5,4,7}".parse::<Machine>().unwrap_err();
+        assert_eq!((1, 12), (error.line, error.column));
+        let error = "[.##.] (3) (1,3) {3,5,4}".parse::<Machine>().unwrap_err();
+        assert_eq!((1, 18), (error.line, error.column));
+    }
+
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/10.txt").unwrap();
-        assert_eq!(Some(7), part_one(&input));
+        assert_eq!(Ok(7), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/10.txt").unwrap();
-        assert_eq!(Some(33), part_two(&input));
+        assert_eq!(Ok(33), part_two(&input));
     }
 }
@@ -1,76 +1,35 @@
-use std::{collections::HashMap, fs};
-
-use cached::proc_macro::cached;
-
-fn part_one(input: &str) -> Option<u32> {
-    let graph: HashMap<&str, Vec<&str>> = input
-        .lines()
-        .map(|l| {
-            let (node, outputs) = l.split_once(": ").unwrap();
-            let outputs: Vec<&str> = outputs.split(" ").collect();
-            (node, outputs)
-        })
-        .collect();
-
-    let mut stack = vec!["you"];
-    let mut count = 0;
-
-    while let Some(curr) = stack.pop() {
-        if curr == "out" {
-            count += 1;
-            continue;
-        }
-
-        for node in graph[curr].clone() {
-            stack.push(node);
-        }
-    }
-
-    Some(count)
-}
-
-#[cached(
-    key = "(String, bool, bool)",
-    convert = r#"{ (curr.clone(), visited_dac, visited_fft) }"#
-)]
-fn dfs_part_2(
-    graph: &HashMap<String, Vec<String>>,
-    curr: String,
-    visited_dac: bool,
-    visited_fft: bool,
-) -> u64 {
-    if curr == "out" {
-        if visited_dac && visited_fft {
-            return 1;
-        }
-        return 0;
-    }
-
-    graph[curr.as_str()]
+use std::fs;
+
+use advent_of_code::{
+    dag,
+    error::{AocError, AocResult},
+    graph::Graph,
+};
+
+fn count_paths(input: &str, from: &str, to: &str, required: &[&str]) -> AocResult<u64> {
+    let graph = Graph::parse_adjacency(input)?;
+    let device = |name: &str| {
+        graph
+            .id(name)
+            .ok_or_else(|| AocError::assumption(format!("no device named {}", name)))
+    };
+
+    let required = required
         .iter()
-        .map(|node| {
-            if node == "dac" {
-                dfs_part_2(graph, node.clone(), true, visited_fft)
-            } else if node == "fft" {
-                dfs_part_2(graph, node.clone(), visited_dac, true)
-            } else {
-                dfs_part_2(graph, node.clone(), visited_dac, visited_fft)
-            }
-        })
-        .sum()
+        .map(|name| device(name))
+        .collect::<AocResult<Vec<u32>>>()?;
+    dag::count_paths(&graph, device(from)?, device(to)?, &required).map_err(|cycle| {
+        let devices: Vec<&str> = cycle.nodes.iter().map(|&n| graph.name(n)).collect();
+        AocError::assumption(format!("devices {} form a cycle", devices.join(" -> ")))
+    })
 }
 
-fn part_two(input: &str) -> Option<u64> {
-    let graph: HashMap<String, Vec<String>> = input
-        .lines()
-        .map(|l| {
-            let (node, outputs) = l.split_once(":").unwrap();
-            let outputs: Vec<String> = outputs.split_whitespace().map(|s| s.to_string()).collect();
-            (node.to_string(), outputs)
-        })
-        .collect();
+fn part_one(input: &str) -> AocResult<u64> {
+    count_paths(input, "you", "out", &[])
+}
 
-    Some(dfs_part_2(&graph, String::from("svr"), false, false))
+fn part_two(input: &str) -> AocResult<u64> {
+    count_paths(input, "svr", "out", &["dac", "fft"])
 }
 
 fn main() {
@@ -87,12 +46,12 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/11_1.txt").unwrap();
-        assert_eq!(Some(5), part_one(&input));
+        assert_eq!(Ok(5), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/11_2.txt").unwrap();
-        assert_eq!(Some(2), part_two(&input));
+        assert_eq!(Ok(2), part_two(&input));
     }
 }
@@ -1,41 +1,135 @@
 use std::fs;
 
-fn part_one(input: &str) -> Option<u32> {
-    // Parse shapes areas and tree requirements
-    let mut lines = input.lines();
-    let mut areas: Vec<u64> = vec![];
-    let mut trees: Vec<(u64, Vec<u64>)> = vec![];
-    while let Some(line) = lines.next() {
-        if line.contains("x") {
-            let mut terms= line
-                .split(|c: char| !c.is_ascii_digit())
-                .filter_map(|s| if !s.is_empty() {s.parse().ok()} else {None});
-            trees.push((terms.next().unwrap() * terms.next().unwrap(), terms.collect()));
-            continue;
-        }
+use advent_of_code::{
+    assumptions::{self, Assumption},
+    error::AocResult,
+    parse::{self, Cursor, ParseResult},
+};
+
+// Area of every shape, then the size of each tree with the presents it needs
+type Trees = (Vec<u64>, Vec<((u64, u64), Vec<u64>)>);
 
-        if line.contains(":") {
-            let area = (0..3).map(|_| lines.next().unwrap())
-                .map(|l| l.as_bytes().iter().filter(|c| **c == b'#').count() as u64)
-                .sum();
+// Packing presents is hard in general, but the trees of the real input either have room
+// for every present in its own 3x3 box or are too small even for the area of the presents
+const ASSUMPTIONS: [Assumption<Trees>; 1] = [Assumption::new(
+    "trees either fit the presents side by side or are smaller than their area",
+    |(areas, trees)| {
+        trees.iter().all(|((width, height), shapes)| {
+            let presents = shapes.iter().sum::<u64>();
+            let required = shapes.iter().zip(areas).map(|(n, a)| n * a).sum::<u64>();
+            (width / 3) * (height / 3) >= presents || width * height < required
+        })
+    },
+)];
+
+// Nothing but spaces may follow on the line
+fn end_of_line(cursor: &mut Cursor) -> ParseResult<()> {
+    let end = *cursor;
+    if cursor.line().trim().is_empty() {
+        Ok(())
+    } else {
+        Err(end.error("Expected the end of the line"))
+    }
+}
+
+// The numbered 3x3 shapes like `0:` then `###`, `##.`, `##.`, then trees like `4x4: 0 2`
+fn parse(input: &str) -> ParseResult<Trees> {
+    let mut cursor = Cursor::new(input);
+    let mut areas: Vec<u64> = vec![];
+    let mut trees: Vec<((u64, u64), Vec<u64>)> = vec![];
+    cursor.skip_whitespace();
+    while !cursor.is_empty() {
+        let start = cursor;
+        let number: u64 = cursor.integer()?;
+        if cursor.peek() == Some('x') {
+            cursor.char('x')?;
+            let height = cursor.integer()?;
+            cursor.char(':')?;
+            let counts = cursor;
+            let shapes: Vec<u64> = parse::integers(cursor.line()).map_err(|e| e.within(&counts))?;
+            if shapes.len() != areas.len() {
+                let message = format!("Expected {} present counts", areas.len());
+                return Err(counts.error(message));
+            }
+            trees.push(((number, height), shapes));
+        } else {
+            if !trees.is_empty() || number != areas.len() as u64 {
+                return Err(start.error(format!("Expected shape {}", areas.len())));
+            }
+            cursor.char(':')?;
+            end_of_line(&mut cursor)?;
+            let mut area = 0;
+            for _ in 0..3 {
+                let row = cursor;
+                if cursor.take_while(|c| c == '#' || c == '.').len() != 3 {
+                    return Err(row.error("Expected 3 '#' or '.'"));
+                }
+                area += row.rest()[..3].matches('#').count() as u64;
+                end_of_line(&mut cursor)?;
+            }
             areas.push(area);
         }
+        cursor.skip_whitespace();
     }
 
+    Ok((areas, trees))
+}
+
+fn part_one(input: &str) -> AocResult<u32> {
+    let trees = parse(input)?;
+    assumptions::check(&trees, &ASSUMPTIONS)?;
+    let (areas, trees) = trees;
+
     // Count only trees that can fit the presents
     let count = trees
         .iter()
-        .filter(|(area, shapes)| {
+        .filter(|((width, height), shapes)| {
             let required = shapes.iter().zip(&areas).map(|(n, a)| (*n) * a).sum::<u64>();
 
-            *area >= required
+            width * height >= required
         })
         .count();
 
-    Some(count as u32)
+    Ok(count as u32)
 }
 
 fn main() {
     let input = fs::read_to_string("inputs/12.txt").unwrap();
     advent_of_code::solve(1, &input, part_one);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "0:\n###\n##.\n##.\n\n1:\n###\n#..\n###\n\n6x3: 1 1\n4x4: 0 2\n";
+
+    #[test]
+    fn parse_test() {
+        let (areas, trees) = parse(INPUT).unwrap();
+        assert_eq!(vec![7, 7], areas);
+        assert_eq!(vec![((6, 3), vec![1, 1]), ((4, 4), vec![0, 2])], trees);
+    }
+
+    #[test]
+    fn invalid_test() {
+        let position = |input| parse(input).map_err(|e| (e.line, e.column));
+        // A shape with a short row, a tree without every count, a missing height
+        assert_eq!(Err((3, 1)), position("0:\n###\n##\n##.\n"));
+        assert_eq!(Err((5, 5)), position("0:\n###\n##.\n##.\n6x3: 1 1\n"));
+        assert_eq!(Err((1, 3)), position("6x: 1\n"));
+        assert!(part_one("0:\n###\n").is_err());
+    }
+
+    #[test]
+    fn assumptions_test() {
+        // The 4x4 tree has room for the area of both presents, but not for two boxes
+        let (areas, trees) = parse(INPUT).unwrap();
+        let fitting = (areas.clone(), trees[..1].to_vec());
+        assert!(assumptions::violated(&fitting, &ASSUMPTIONS).is_empty());
+        assert_eq!(
+            vec!["trees either fit the presents side by side or are smaller than their area"],
+            assumptions::violated(&(areas, trees), &ASSUMPTIONS)
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use std::fs;
 
-fn part_one(input: &str) -> Option<u32> {
-    None
-}
+use advent_of_code::error::{AocError, AocResult};
 
-fn part_two(input: &str) -> Option<u32> {
-    None
+fn part_one(input: &str) -> AocResult<u32> {
+    Err(AocError::NoSolution)
 }
 
+fn part_two(input: &str) -> AocResult<u32> {
+    Err(AocError::NoSolution)
+}
 
 fn main() {
     let input = fs::read_to_string("inputs/{DAY}.txt").unwrap();
@@ -23,13 +24,12 @@ mod tests {
     #[test]
     fn part_one_test() {
         let input = fs::read_to_string("examples/{DAY}.txt").unwrap();
-        assert_eq!(None, part_one(&input));
+        assert_eq!(Err(AocError::NoSolution), part_one(&input));
     }
 
     #[test]
     fn part_two_test() {
         let input = fs::read_to_string("examples/{DAY}.txt").unwrap();
-        assert_eq!(None, part_two(&input));
+        assert_eq!(Err(AocError::NoSolution), part_two(&input));
     }
-
-}
\ No newline at end of file
+}
@@ -0,0 +1,314 @@
+//! Linear algebra over GF(2), the bits with XOR as addition, like toggling lights.
+
+use std::ops::BitXorAssign;
+
+/// Vector of bits packed in 64 bits words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Vector with the given bits set.
+    pub fn from_indices(len: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut vector = Self::zeros(len);
+        indices.into_iter().for_each(|i| vector.set(i, true));
+        vector
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "Bit out of bounds");
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "Bit out of bounds");
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    /// Number of bits set.
+    pub fn weight(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Indices of the bits set, in order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&i| self.get(i))
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        let (a, b) = (self.get(i), self.get(j));
+        self.set(i, b);
+        self.set(j, a);
+    }
+}
+
+impl FromIterator<bool> for BitVector {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        Self::from_indices(bits.len(), (0..bits.len()).filter(|&i| bits[i]))
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    fn bitxor_assign(&mut self, other: &BitVector) {
+        assert_eq!(self.len, other.len, "Different lengths");
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+/// Matrix of bits, stored as packed rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: Vec<BitVector>,
+    columns: usize,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        Self {
+            rows: vec![BitVector::zeros(columns); rows],
+            columns,
+        }
+    }
+
+    /// Matrix whose columns are the given vectors, like the lights toggled by
+    /// each button.
+    pub fn from_columns(rows: usize, columns: &[BitVector]) -> Self {
+        let mut matrix = Self::zeros(rows, columns.len());
+        for (j, column) in columns.iter().enumerate() {
+            column.ones().for_each(|i| matrix.set(i, j, true));
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> bool {
+        self.rows[row].get(column)
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: bool) {
+        self.rows[row].set(column, value);
+    }
+
+    /// `self * x`.
+    pub fn multiply(&self, x: &BitVector) -> BitVector {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.words
+                    .iter()
+                    .zip(&x.words)
+                    .map(|(a, b)| (a & b).count_ones())
+                    .sum::<u32>()
+                    % 2
+                    == 1
+            })
+            .collect()
+    }
+
+    /// Reduced row echelon form by Gaussian elimination, with the pivot column of
+    /// each of the first rows (the others are zero). The same row operations are
+    /// applied to `rhs`.
+    fn reduce(&self, rhs: &mut BitVector) -> (Matrix, Vec<usize>) {
+        let mut reduced = self.clone();
+        let mut pivots = vec![];
+        for column in 0..self.columns {
+            let row = pivots.len();
+            let Some(pivot) = (row..self.rows()).find(|&i| reduced.get(i, column)) else {
+                continue;
+            };
+            reduced.rows.swap(row, pivot);
+            rhs.swap(row, pivot);
+
+            let pivot_row = reduced.rows[row].clone();
+            for i in 0..self.rows() {
+                if i != row && reduced.get(i, column) {
+                    reduced.rows[i] ^= &pivot_row;
+                    let value = rhs.get(i) ^ rhs.get(row);
+                    rhs.set(i, value);
+                }
+            }
+            pivots.push(column);
+        }
+        (reduced, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.reduce(&mut BitVector::zeros(self.rows())).1.len()
+    }
+
+    /// Basis of the vectors `x` with `self * x = 0`: adding any of them to a
+    /// solution gives another solution.
+    pub fn nullspace(&self) -> Vec<BitVector> {
+        let (reduced, pivots) = self.reduce(&mut BitVector::zeros(self.rows()));
+        (0..self.columns)
+            .filter(|column| !pivots.contains(column))
+            .map(|free| {
+                let mut x = BitVector::zeros(self.columns);
+                x.set(free, true);
+                for (row, &column) in pivots.iter().enumerate() {
+                    if reduced.get(row, free) {
+                        x.set(column, true);
+                    }
+                }
+                x
+            })
+            .collect()
+    }
+
+    /// A solution of `self * x = b` (with all the free variables at 0), `None`
+    /// when there is none.
+    pub fn solve(&self, b: &BitVector) -> Option<BitVector> {
+        let mut rhs = b.clone();
+        let (_, pivots) = self.reduce(&mut rhs);
+        if (pivots.len()..self.rows()).any(|row| rhs.get(row)) {
+            return None;
+        }
+
+        let mut x = BitVector::zeros(self.columns);
+        for (row, &column) in pivots.iter().enumerate() {
+            x.set(column, rhs.get(row));
+        }
+        Some(x)
+    }
+
+    /// Solution of `self * x = b` with the fewest bits set, like the fewest
+    /// buttons to press. Tries every combination of the nullspace, so it is
+    /// exponential in its dimension (the number of free variables).
+    pub fn min_weight_solution(&self, b: &BitVector) -> Option<BitVector> {
+        let mut x = self.solve(b)?;
+        let nullspace = self.nullspace();
+        assert!(nullspace.len() < 64, "Nullspace too large to enumerate");
+
+        // Gray code order, each combination is one XOR away from the previous one
+        let mut best = x.clone();
+        for i in 1..1u64 << nullspace.len() {
+            x ^= &nullspace[i.trailing_zeros() as usize];
+            if x.weight() < best.weight() {
+                best = x.clone();
+            }
+        }
+        Some(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lights out on a n x n grid: each cell toggles itself and its neighbours
+    fn lights_out(n: usize) -> Matrix {
+        let columns: Vec<BitVector> = (0..n * n)
+            .map(|cell| {
+                let (y, x) = (cell / n, cell % n);
+                let mut toggled = vec![cell];
+                (y > 0).then(|| toggled.push(cell - n));
+                (y + 1 < n).then(|| toggled.push(cell + n));
+                (x > 0).then(|| toggled.push(cell - 1));
+                (x + 1 < n).then(|| toggled.push(cell + 1));
+                BitVector::from_indices(n * n, toggled)
+            })
+            .collect();
+        Matrix::from_columns(n * n, &columns)
+    }
+
+    #[test]
+    fn test_bit_vector() {
+        let mut v = BitVector::from_indices(130, [0, 64, 129]);
+        assert_eq!(3, v.weight());
+        assert_eq!(vec![0, 64, 129], v.ones().collect::<Vec<_>>());
+        v ^= &BitVector::from_indices(130, [64, 100]);
+        assert_eq!(vec![0, 100, 129], v.ones().collect::<Vec<_>>());
+        v.set(0, false);
+        assert!(!v.get(0));
+        assert!(!v.is_zero());
+    }
+
+    #[test]
+    fn test_rank_nullspace() {
+        // Third row is the sum of the first two
+        let rows = [[1, 1, 0, 1], [0, 1, 1, 0], [1, 0, 1, 1]];
+        let mut matrix = Matrix::zeros(3, 4);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &bit) in row.iter().enumerate() {
+                matrix.set(i, j, bit == 1);
+            }
+        }
+        assert_eq!(2, matrix.rank());
+
+        let nullspace = matrix.nullspace();
+        assert_eq!(2, nullspace.len());
+        assert!(nullspace.iter().all(|x| matrix.multiply(x).is_zero()));
+
+        assert_eq!(None, matrix.solve(&BitVector::from_indices(3, [0])));
+        let b = BitVector::from_indices(3, [0, 2]);
+        assert_eq!(b, matrix.multiply(&matrix.solve(&b).unwrap()));
+    }
+
+    #[test]
+    fn test_lights_out() {
+        // Lights out 3 x 3 is always solvable, check the minimum by brute force
+        let matrix = lights_out(3);
+        assert_eq!(9, matrix.rank());
+        let all_on = BitVector::from_indices(9, 0..9);
+        let best = matrix.min_weight_solution(&all_on).unwrap();
+        assert_eq!(all_on, matrix.multiply(&best));
+
+        let brute_force = (0..1usize << 9)
+            .map(|presses| BitVector::from_indices(9, (0..9).filter(|i| presses >> i & 1 == 1)))
+            .filter(|x| matrix.multiply(x) == all_on)
+            .map(|x| x.weight())
+            .min();
+        assert_eq!(Some(best.weight()), brute_force);
+
+        // 5 x 5 has a nullspace of dimension 2, and wider than 64 lights works too
+        let matrix = lights_out(5);
+        assert_eq!(2, matrix.nullspace().len());
+        assert_eq!(
+            Some(15),
+            matrix
+                .min_weight_solution(&BitVector::from_indices(25, 0..25))
+                .map(|x| x.weight())
+        );
+        let matrix = lights_out(9);
+        let b = matrix.multiply(&BitVector::from_indices(81, [3, 50, 77]));
+        let x = matrix.min_weight_solution(&b).unwrap();
+        assert_eq!(b, matrix.multiply(&x));
+        assert!(x.weight() <= 3);
+    }
+}
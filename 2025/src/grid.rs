@@ -1,9 +1,13 @@
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display},
     str::FromStr,
 };
 
-use crate::coordinate::Coordinate;
+use crate::{
+    coordinate::Coordinate,
+    parse::{Cursor, ParseResult},
+};
 
 #[derive(Clone, Debug)]
 pub struct Grid<T> {
@@ -53,6 +57,65 @@ impl<T> Grid<T> {
             && coordinate.x >= 0
             && coordinate.x < self.width as i32
     }
+
+    /// Every coordinate of the grid, row by row.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + use<T> {
+        let (height, width) = (self.height as i32, self.width as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Coordinate::new(y, x)))
+    }
+}
+
+/// Grid storing only the cells which were set, for unbounded or mostly empty areas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Coordinate, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, coord: &Coordinate) -> Option<&T> {
+        self.cells.get(coord)
+    }
+
+    pub fn set(&mut self, coordinate: &Coordinate, value: T) {
+        self.cells.insert(*coordinate, value);
+    }
+
+    pub fn remove(&mut self, coordinate: &Coordinate) -> Option<T> {
+        self.cells.remove(coordinate)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Coordinates of the cells set, in no particular order.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.cells.keys().copied()
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Coordinate, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinate, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
 }
 
 impl<T> Grid<T>
@@ -113,6 +176,26 @@ impl Grid<char> {
         }
     }
 
+    /// Same as [Grid::new_chars], failing on a character not in `allowed` or on
+    /// lines of different lengths.
+    pub fn parse_chars(input: &str, allowed: &str) -> ParseResult<Self> {
+        let mut cursor = Cursor::new(input);
+        let mut width = None;
+        while !cursor.is_empty() {
+            let line = cursor.take_while(|c| allowed.contains(c));
+            match cursor.peek() {
+                None | Some('\n' | '\r') => {}
+                Some(c) => return Err(cursor.error(format!("Unexpected {:?}", c))),
+            }
+            let width = *width.get_or_insert(line.len());
+            if line.len() != width {
+                return Err(cursor.error(format!("Expected lines of {} characters", width)));
+            }
+            cursor.line();
+        }
+        Ok(Self::new_chars(input))
+    }
+
     pub fn find_first(&self, arg: char) -> Option<Coordinate> {
         for y in 0..self.height {
             for x in 0..self.width {
@@ -0,0 +1,168 @@
+//! Small integer linear programs, solved exactly without floating point.
+
+use itertools::Itertools;
+
+use crate::math;
+
+// Divides the row by the GCD of its values, to keep them small
+fn normalise(row: &mut [i128]) {
+    let divisor = row.iter().fold(0, |g, &v| math::gcd_wide(g, v));
+    if divisor > 1 {
+        row.iter_mut().for_each(|v| *v /= divisor);
+    }
+}
+
+/// Reduced row echelon form of `[matrix | b]`: each row is scaled so it only
+/// holds integers, which keeps the elimination exact. Gives the pivot column of
+/// each of the first rows, `None` when the system has no solution.
+fn reduce(matrix: &[Vec<i64>], b: &[i64], columns: usize) -> Option<(Vec<Vec<i128>>, Vec<usize>)> {
+    let mut rows: Vec<Vec<i128>> = matrix
+        .iter()
+        .zip(b)
+        .map(|(row, &v)| row.iter().chain([&v]).map(|&x| x as i128).collect())
+        .collect();
+
+    let mut pivots = vec![];
+    for column in 0..columns {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len()).find(|&i| rows[i][column] != 0) else {
+            continue;
+        };
+        rows.swap(row, pivot);
+
+        let pivot_row = rows[row].clone();
+        for (i, other) in rows.iter_mut().enumerate() {
+            let factor = other[column];
+            if i != row && factor != 0 {
+                other
+                    .iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, p)| *v = *v * pivot_row[column] - p * factor);
+                normalise(other);
+            }
+        }
+        pivots.push(column);
+    }
+
+    // Rows left are 0 = rhs
+    rows[pivots.len()..]
+        .iter()
+        .all(|row| row[columns] == 0)
+        .then_some((rows, pivots))
+}
+
+/// Solution of `matrix * x = b` with every `x >= 0` an integer and the smallest
+/// sum, like the fewest button presses, `None` when there is none.
+///
+/// The matrix must not have negative values, so each variable is at most the
+/// smallest value of `b` it adds to. After a Gaussian elimination, only the free
+/// variables are left to choose: all their values within these bounds are tried,
+/// which is exponential in their number.
+pub fn min_sum_solution(matrix: &[Vec<i64>], b: &[i64]) -> Option<Vec<i64>> {
+    assert!(
+        matrix.iter().flatten().all(|&v| v >= 0),
+        "Negative coefficient"
+    );
+    let columns = matrix.first().map_or(0, Vec::len);
+    let (rows, pivots) = reduce(matrix, b, columns)?;
+
+    let bounds: Vec<i64> = (0..columns)
+        .map(|j| {
+            (0..matrix.len())
+                .filter(|&i| matrix[i][j] > 0)
+                .map(|i| b[i] / matrix[i][j])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect();
+    let free: Vec<usize> = (0..columns).filter(|c| !pivots.contains(c)).collect();
+
+    // A single empty choice without free variables
+    let choices = free
+        .iter()
+        .map(|&f| 0..=bounds[f])
+        .multi_cartesian_product();
+    let mut best: Option<(i64, Vec<i64>)> = None;
+    for values in choices {
+        let free_sum: i64 = values.iter().sum();
+        if best.as_ref().is_some_and(|(sum, _)| free_sum >= *sum) {
+            continue;
+        }
+
+        let mut x = vec![0; columns];
+        free.iter().zip(&values).for_each(|(&f, &v)| x[f] = v);
+
+        // pivot * x[column] + the free variables = rhs, in integers
+        let solved = pivots.iter().zip(&rows).all(|(&column, row)| {
+            let rhs = row[columns] - free.iter().map(|&f| row[f] * x[f] as i128).sum::<i128>();
+            let value = rhs / row[column];
+            x[column] = value as i64;
+            rhs % row[column] == 0 && value >= 0
+        });
+        let sum = x.iter().sum();
+        if solved && best.as_ref().is_none_or(|(best, _)| sum < *best) {
+            best = Some((sum, x));
+        }
+    }
+
+    // Checks the answer against the original system
+    best.map(|(_, x)| x).filter(|x| {
+        matrix
+            .iter()
+            .zip(b)
+            .all(|(row, &v)| row.iter().zip(x).map(|(a, x)| a * x).sum::<i64>() == v)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Column j has a 1 for every row in buttons[j]
+    fn matrix(rows: usize, buttons: &[&[usize]]) -> Vec<Vec<i64>> {
+        (0..rows)
+            .map(|i| buttons.iter().map(|b| b.contains(&i) as i64).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_min_sum_solution() {
+        // First machine of 2025 day 10
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let a = matrix(4, &buttons);
+        let b = [3, 5, 4, 7];
+        let x = min_sum_solution(&a, &b).unwrap();
+        assert_eq!(10, x.iter().sum::<i64>());
+
+        // Nothing smaller by brute force
+        let smallest = (0..6)
+            .map(|_| 0..=7)
+            .multi_cartesian_product()
+            .filter(|x| {
+                a.iter()
+                    .zip(&b)
+                    .all(|(row, &v)| row.iter().zip(x).map(|(a, x)| a * x).sum::<i64>() == v)
+            })
+            .map(|x| x.iter().sum::<i64>())
+            .min();
+        assert_eq!(Some(10), smallest);
+    }
+
+    #[test]
+    fn test_exact() {
+        // Unique solution (2, 3), found without rounding
+        assert_eq!(
+            Some(vec![2, 3]),
+            min_sum_solution(&[vec![3, 1], vec![1, 2]], &[9, 8])
+        );
+        // Only a fractional solution (1.5)
+        assert_eq!(None, min_sum_solution(&[vec![2]], &[3]));
+        // Only a negative solution
+        assert_eq!(None, min_sum_solution(&[vec![1, 1], vec![0, 1]], &[1, 2]));
+        // Inconsistent
+        assert_eq!(None, min_sum_solution(&[vec![1, 1], vec![1, 1]], &[1, 2]));
+        // A variable which does nothing stays at 0
+        assert_eq!(Some(vec![4, 0]), min_sum_solution(&[vec![1, 0]], &[4]));
+        assert_eq!(Some(vec![]), min_sum_solution(&[], &[]));
+    }
+}
@@ -1,15 +1,24 @@
 use std::{fmt::Display, time::Instant};
 
+use error::Answer;
+
 pub mod coordinate;
+pub mod gf2;
 pub mod grid;
+pub mod ilp;
+pub mod simulation;
+
+pub use aoc_common::{assumptions, dag, error, graph, interval, math, parse, search};
 
 
-pub fn solve<T: Display>(part: usize, input: &str, solve_fn: impl Fn(&str) -> Option<T>) {
+/// Runs a solution and reports its answer, or why it failed.
+pub fn solve<T: Display, A: Answer<T>>(part: usize, input: &str, solve_fn: impl Fn(&str) -> A) {
     let start = Instant::now();
-    let result =  solve_fn(input);
+    let result =  solve_fn(input).into_result();
     let duration = start.elapsed();
 
-    if let Some(res) = result {
-        println!("🎄 Part {} solution: {} (took {:?})", part, res, duration);
+    match result {
+        Ok(res) => println!("🎄 Part {} solution: {} (took {:?})", part, res, duration),
+        Err(e) => println!("🎄 Part {} failed: {} (took {:?})", part, e, duration),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,208 @@
+//! Cellular automata on grids: every cell takes a new value from the cells
+//! around it, step after step. Moves of whole pieces, like a falling rock or a
+//! row of pushed boxes, are not local to a cell and do not fit.
+
+use crate::{
+    coordinate::Coordinate,
+    grid::{Grid, SparseGrid},
+};
+
+/// Grid a simulation can run on.
+pub trait Cells<T> {
+    /// Cells updated by the rules, row by row.
+    fn cells(&self) -> Vec<Coordinate>;
+    fn cell(&self, coordinate: &Coordinate) -> Option<&T>;
+    fn update(&mut self, coordinate: &Coordinate, value: T);
+}
+
+impl<T> Cells<T> for Grid<T> {
+    fn cells(&self) -> Vec<Coordinate> {
+        self.coordinates().collect()
+    }
+
+    fn cell(&self, coordinate: &Coordinate) -> Option<&T> {
+        self.get(coordinate)
+    }
+
+    fn update(&mut self, coordinate: &Coordinate, value: T) {
+        self.set(coordinate, value);
+    }
+}
+
+/// Only the cells set are updated, rules must set new cells themselves.
+impl<T> Cells<T> for SparseGrid<T> {
+    fn cells(&self) -> Vec<Coordinate> {
+        let mut cells: Vec<Coordinate> = self.coordinates().collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells
+    }
+
+    fn cell(&self, coordinate: &Coordinate) -> Option<&T> {
+        self.get(coordinate)
+    }
+
+    fn update(&mut self, coordinate: &Coordinate, value: T) {
+        self.set(coordinate, value);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Update {
+    /// Every cell sees the grid as it was before the step, like the game of life.
+    Synchronous,
+    /// Cells are updated in place row by row, later cells see the earlier changes.
+    Asynchronous,
+}
+
+type Rule<'a, G, T> = Box<dyn Fn(&G, &Coordinate, &T) -> T + 'a>;
+type Hook<'a, G> = Box<dyn FnMut(usize, &G, usize) + 'a>;
+
+/// Applies a rule giving the next value of every cell, step after step.
+pub struct Simulation<'a, G, T> {
+    pub grid: G,
+    pub steps: usize,
+    update: Update,
+    rule: Rule<'a, G, T>,
+    hooks: Vec<Hook<'a, G>>,
+}
+
+impl<'a, G, T> Simulation<'a, G, T>
+where
+    G: Cells<T> + Clone,
+    T: Clone + PartialEq,
+{
+    pub fn new(grid: G, update: Update, rule: impl Fn(&G, &Coordinate, &T) -> T + 'a) -> Self {
+        Self {
+            grid,
+            steps: 0,
+            update,
+            rule: Box::new(rule),
+            hooks: vec![],
+        }
+    }
+
+    /// Calls `hook` after every step with the step number (from 1), the grid and
+    /// the number of cells changed, e.g. to draw the grid.
+    pub fn on_step(mut self, hook: impl FnMut(usize, &G, usize) + 'a) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Runs one step, giving the number of cells changed.
+    pub fn step(&mut self) -> usize {
+        let mut changed = 0;
+        match self.update {
+            Update::Synchronous => {
+                let mut next = self.grid.clone();
+                for coordinate in self.grid.cells() {
+                    let value = self.grid.cell(&coordinate).unwrap();
+                    let new = (self.rule)(&self.grid, &coordinate, value);
+                    if new != *value {
+                        next.update(&coordinate, new);
+                        changed += 1;
+                    }
+                }
+                self.grid = next;
+            }
+            Update::Asynchronous => {
+                for coordinate in self.grid.cells() {
+                    let value = self.grid.cell(&coordinate).unwrap();
+                    let new = (self.rule)(&self.grid, &coordinate, value);
+                    if new != *value {
+                        self.grid.update(&coordinate, new);
+                        changed += 1;
+                    }
+                }
+            }
+        }
+
+        self.steps += 1;
+        for hook in self.hooks.iter_mut() {
+            hook(self.steps, &self.grid, changed);
+        }
+        changed
+    }
+
+    /// Runs `n` steps, giving the number of cells changed by each.
+    pub fn run(&mut self, n: usize) -> Vec<usize> {
+        (0..n).map(|_| self.step()).collect()
+    }
+
+    /// Runs until a step changes nothing, giving the number of cells changed by
+    /// each step before it.
+    pub fn run_until_stable(&mut self) -> Vec<usize> {
+        let mut counts = vec![];
+        loop {
+            match self.step() {
+                0 => return counts,
+                changed => counts.push(changed),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::NORTH;
+
+    // Game of life rule on a closed grid
+    fn life(grid: &Grid<bool>, coordinate: &Coordinate, alive: &bool) -> bool {
+        let neighbours = coordinate
+            .around_all()
+            .iter()
+            .filter(|c| grid.is_true(c))
+            .count();
+        neighbours == 3 || (*alive && neighbours == 2)
+    }
+
+    fn blinker() -> Grid<bool> {
+        let mut grid = Grid::new_false(5, 5);
+        (1..4).for_each(|x| grid.set(&Coordinate::new(2, x), true));
+        grid
+    }
+
+    #[test]
+    fn test_synchronous() {
+        let mut steps = vec![];
+        let mut simulation = Simulation::new(blinker(), Update::Synchronous, life)
+            .on_step(|step, _, changed| steps.push((step, changed)));
+
+        // The horizontal line turns vertical, then back
+        assert_eq!(4, simulation.step());
+        assert!(simulation.grid.is_true(&Coordinate::new(1, 2)));
+        assert!(simulation.grid.is_false(&Coordinate::new(2, 1)));
+        assert_eq!(4, simulation.step());
+        assert!(simulation.grid.is_true(&Coordinate::new(2, 1)));
+
+        drop(simulation);
+        assert_eq!(vec![(1, 4), (2, 4)], steps);
+    }
+
+    #[test]
+    fn test_asynchronous() {
+        // Water falls from the source to the floor in a single step
+        let grid: SparseGrid<char> = "..S..\n.....\n.....\n#####"
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(move |(x, c)| (Coordinate::new(y as i32, x as i32), c))
+            })
+            .collect();
+
+        let mut simulation = Simulation::new(grid, Update::Asynchronous, |grid, coordinate, c| {
+            let above = grid.get(&(*coordinate + NORTH));
+            if *c == '.' && matches!(above, Some('S') | Some('~')) {
+                '~'
+            } else {
+                *c
+            }
+        });
+
+        assert_eq!(vec![2], simulation.run_until_stable());
+        assert_eq!(2, simulation.steps);
+        assert_eq!(Some(&'~'), simulation.grid.get(&Coordinate::new(2, 2)));
+    }
+}
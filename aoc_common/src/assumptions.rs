@@ -0,0 +1,75 @@
+use std::env;
+
+use crate::error::{AocError, AocResult};
+
+/// Property of the real input a solution relies on without handling the
+/// general case, like lines being only horizontal or vertical.
+pub struct Assumption<T: ?Sized> {
+    pub description: &'static str,
+    pub holds: fn(&T) -> bool,
+}
+
+impl<T: ?Sized> Assumption<T> {
+    pub const fn new(description: &'static str, holds: fn(&T) -> bool) -> Self {
+        Self { description, holds }
+    }
+}
+
+/// Assumptions are checked in debug builds (so by the tests), or when the
+/// solution runs with `--check-assumptions`.
+pub fn enabled() -> bool {
+    cfg!(debug_assertions) || env::args().any(|arg| arg == "--check-assumptions")
+}
+
+/// Every assumption the input violates, in order.
+pub fn violated<'a, T: ?Sized>(input: &T, assumptions: &'a [Assumption<T>]) -> Vec<&'a str> {
+    assumptions
+        .iter()
+        .filter(|a| !(a.holds)(input))
+        .map(|a| a.description)
+        .collect()
+}
+
+/// Fails with all the violated assumptions when checking is enabled.
+pub fn check<T: ?Sized>(input: &T, assumptions: &[Assumption<T>]) -> AocResult<()> {
+    if enabled() {
+        require(input, assumptions)
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails with all the violated assumptions, whether checking is enabled or not.
+pub fn require<T: ?Sized>(input: &T, assumptions: &[Assumption<T>]) -> AocResult<()> {
+    let violated = violated(input, assumptions);
+    if violated.is_empty() {
+        Ok(())
+    } else {
+        Err(AocError::assumption(violated.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSUMPTIONS: [Assumption<[u32]>; 2] = [
+        Assumption::new("not empty", |v| !v.is_empty()),
+        Assumption::new("sorted", |v| v.is_sorted()),
+    ];
+
+    #[test]
+    fn test_violated() {
+        assert!(violated(&[1, 2, 3][..], &ASSUMPTIONS).is_empty());
+        assert_eq!(vec!["sorted"], violated(&[2, 1][..], &ASSUMPTIONS));
+    }
+
+    #[test]
+    fn test_require() {
+        assert_eq!(Ok(()), require(&[1][..], &ASSUMPTIONS));
+        assert_eq!(
+            Err(AocError::assumption("not empty")),
+            require(&[][..], &ASSUMPTIONS)
+        );
+    }
+}
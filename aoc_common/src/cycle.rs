@@ -0,0 +1,213 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a sequence of states starts repeating: the state at step `start + length`
+/// is the same as the one at step `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// First step with the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare on the states `initial`, `step(initial)`, ..., which
+/// must repeat at some point. Only keeps two states in memory.
+pub fn floyd<T: Clone + PartialEq>(initial: T, step: impl Fn(&T) -> T) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // The hare is now a multiple of the length ahead, walking both at the same
+    // pace from there they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, same as [floyd] with fewer calls to `step`.
+pub fn brent<T: Clone + PartialEq>(initial: T, step: impl Fn(&T) -> T) -> Cycle {
+    // Look for the length with the tortoise waiting at powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then walk both a length apart until they meet at the start
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Runs `step` on `state` until it gives the same `key` twice, or until `step`
+/// returns `false` to stop the simulation (giving `None`). The key must identify
+/// everything of the state the next steps depend on.
+pub fn detect<S, K: Hash + Eq>(
+    state: &mut S,
+    mut step: impl FnMut(&mut S) -> bool,
+    key: impl Fn(&S) -> K,
+) -> Option<Cycle> {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut idx = 0;
+    loop {
+        if let Some(start) = seen.insert(key(state), idx) {
+            return Some(Cycle {
+                start,
+                length: idx - start,
+            });
+        }
+        if !step(state) {
+            return None;
+        }
+        idx += 1;
+    }
+}
+
+/// Value of `measure` after `n` steps from `state`. Once `key` repeats, the
+/// remaining cycles are skipped, assuming every cycle changes `measure` by the
+/// same amount (like the height of a growing tower).
+pub fn simulate_until<S, K: Hash + Eq>(
+    mut state: S,
+    n: usize,
+    mut step: impl FnMut(&mut S),
+    key: impl Fn(&S) -> K,
+    measure: impl Fn(&S) -> i64,
+) -> i64 {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut measures: Vec<i64> = vec![];
+    for idx in 0..n {
+        measures.push(measure(&state));
+        if let Some(start) = seen.insert(key(&state), idx) {
+            let length = idx - start;
+            let cycles = ((n - start) / length) as i64;
+            let delta = measures[idx] - measures[start];
+            return measures[start + (n - start) % length] + cycles * delta;
+        }
+        step(&mut state);
+    }
+    measure(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, ..., 10, then 5, ..., 10 forever
+    fn next(x: &u32) -> u32 {
+        if *x < 10 {
+            x + 1
+        } else {
+            5
+        }
+    }
+
+    #[test]
+    fn test_floyd_brent() {
+        let expected = Cycle {
+            start: 5,
+            length: 6,
+        };
+        assert_eq!(expected, floyd(0, next));
+        assert_eq!(expected, brent(0, next));
+        assert_eq!(
+            Cycle {
+                start: 0,
+                length: 1
+            },
+            brent(0, |x| *x)
+        );
+        assert_eq!(7, expected.equivalent(19));
+        assert_eq!(3, expected.equivalent(3));
+    }
+
+    #[test]
+    fn test_detect() {
+        let mut x = 0;
+        let cycle = detect(
+            &mut x,
+            |x| {
+                *x = next(x);
+                true
+            },
+            |x| *x,
+        );
+        assert_eq!(
+            Some(Cycle {
+                start: 5,
+                length: 6
+            }),
+            cycle
+        );
+        assert_eq!(5, x);
+
+        let stopped = detect(
+            &mut 0,
+            |x| {
+                *x = next(x);
+                *x < 8
+            },
+            |x| *x,
+        );
+        assert_eq!(None, stopped);
+    }
+
+    #[test]
+    fn test_simulate_until() {
+        // Sum of the visited values
+        let step = |(x, sum): &mut (u32, i64)| {
+            *x = next(x);
+            *sum += *x as i64;
+        };
+        for n in [0, 3, 11, 12, 1000] {
+            let mut state = (0, 0);
+            (0..n).for_each(|_| step(&mut state));
+            assert_eq!(
+                state.1,
+                simulate_until((0, 0), n, step, |(x, _)| *x, |(_, sum)| *sum)
+            );
+        }
+    }
+}
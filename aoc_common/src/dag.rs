@@ -0,0 +1,293 @@
+use std::{collections::VecDeque, ops::Add};
+
+use crate::{graph::Graph, search::SearchResult};
+
+/// Nodes forming a cycle, in edge order (the last node points back to the first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub nodes: Vec<u32>,
+}
+
+/// Topological order of the whole graph, or one of its cycles.
+pub fn topological_sort<N, E>(graph: &Graph<N, E>) -> Result<Vec<u32>, Cycle> {
+    let nodes: Vec<u32> = graph.ids().collect();
+    topological_sort_of(graph, &nodes)
+}
+
+/// Topological order of the subgraph induced by `nodes`, ignoring every edge
+/// leaving the subset.
+pub fn topological_sort_of<N, E>(graph: &Graph<N, E>, nodes: &[u32]) -> Result<Vec<u32>, Cycle> {
+    let mut included = vec![false; graph.len()];
+    for &node in nodes {
+        included[node as usize] = true;
+    }
+
+    let mut in_degree = vec![0; graph.len()];
+    for &node in nodes {
+        for next in graph.neighbours(node).filter(|&n| included[n as usize]) {
+            in_degree[next as usize] += 1;
+        }
+    }
+
+    // Kahn's algorithm
+    let mut queue: VecDeque<u32> = nodes
+        .iter()
+        .copied()
+        .filter(|&n| in_degree[n as usize] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for next in graph.neighbours(node).filter(|&n| included[n as usize]) {
+            in_degree[next as usize] -= 1;
+            if in_degree[next as usize] == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if order.len() == nodes.len() {
+        return Ok(order);
+    }
+
+    // Every node left has a predecessor left, walk them back until one repeats
+    let remaining = |n: u32| included[n as usize] && in_degree[n as usize] > 0;
+    let mut walk = vec![*nodes.iter().find(|&&n| remaining(n)).unwrap()];
+    loop {
+        let last = *walk.last().unwrap();
+        let (prev, _) = *graph
+            .predecessors(last)
+            .iter()
+            .find(|(p, _)| remaining(*p))
+            .unwrap();
+
+        if let Some(start) = walk.iter().position(|&n| n == prev) {
+            let mut cycle = walk.split_off(start);
+            cycle.reverse();
+            return Err(Cycle { nodes: cycle });
+        }
+        walk.push(prev);
+    }
+}
+
+/// Whether `sequence` respects every edge between its own nodes, i.e. no node
+/// comes after one of its successors.
+pub fn is_consistent<N, E>(graph: &Graph<N, E>, sequence: &[u32]) -> bool {
+    let mut position = vec![None; graph.len()];
+    for (idx, &node) in sequence.iter().enumerate() {
+        position[node as usize] = Some(idx);
+    }
+
+    sequence.iter().enumerate().all(|(idx, &node)| {
+        graph
+            .neighbours(node)
+            .all(|next| position[next as usize].is_none_or(|p| p > idx))
+    })
+}
+
+/// Number of paths from `from` to `to` visiting all the `required` nodes.
+/// Visited required nodes are tracked as a bitmask, so there can be at most 64
+/// of them (and only a handful in practice, as the state grows with 2^n).
+/// `C` can be any integer type, including big integers.
+pub fn count_paths<N, E, C>(
+    graph: &Graph<N, E>,
+    from: u32,
+    to: u32,
+    required: &[u32],
+) -> Result<C, Cycle>
+where
+    C: Clone + From<u8> + Add<Output = C>,
+{
+    let bit = |node: u32| -> usize {
+        required
+            .iter()
+            .position(|&r| r == node)
+            .map_or(0, |idx| 1 << idx)
+    };
+    let masks = 1 << required.len();
+
+    let order = topological_sort_of(graph, &reachable(graph, from))?;
+    let mut counts = vec![vec![C::from(0); masks]; graph.len()];
+    counts[from as usize][bit(from)] = C::from(1);
+
+    for node in order {
+        for mask in 0..masks {
+            let count = counts[node as usize][mask].clone();
+            for next in graph.neighbours(node) {
+                let next_mask = mask | bit(next);
+                let next_count = counts[next as usize][next_mask].clone();
+                counts[next as usize][next_mask] = next_count + count.clone();
+            }
+        }
+    }
+
+    Ok(counts[to as usize][masks - 1].clone())
+}
+
+/// Cheapest path from `from` to `to`, `None` if `to` is not reachable.
+/// Unlike Dijkstra, weights can be negative as long as the graph has no cycles.
+pub fn shortest_path<N, E, C>(
+    graph: &Graph<N, E>,
+    from: u32,
+    to: u32,
+    weight: impl Fn(&E) -> C,
+) -> Result<Option<SearchResult<u32, C>>, Cycle>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    best_path(graph, from, to, weight, |new, old| new < old)
+}
+
+/// Most expensive path from `from` to `to`, `None` if `to` is not reachable.
+pub fn longest_path<N, E, C>(
+    graph: &Graph<N, E>,
+    from: u32,
+    to: u32,
+    weight: impl Fn(&E) -> C,
+) -> Result<Option<SearchResult<u32, C>>, Cycle>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    best_path(graph, from, to, weight, |new, old| new > old)
+}
+
+fn best_path<N, E, C>(
+    graph: &Graph<N, E>,
+    from: u32,
+    to: u32,
+    weight: impl Fn(&E) -> C,
+    better: impl Fn(C, C) -> bool,
+) -> Result<Option<SearchResult<u32, C>>, Cycle>
+where
+    C: Copy + Ord + Default + Add<Output = C>,
+{
+    let order = topological_sort_of(graph, &reachable(graph, from))?;
+
+    // Best cost to reach every node, with the node it was reached from
+    let mut best: Vec<Option<(C, u32)>> = vec![None; graph.len()];
+    best[from as usize] = Some((C::default(), from));
+
+    for node in order {
+        let Some((cost, _)) = best[node as usize] else {
+            continue;
+        };
+        for (next, edge) in graph.successors(node) {
+            let new_cost = cost + weight(edge);
+            if best[*next as usize].is_none_or(|(old, _)| better(new_cost, old)) {
+                best[*next as usize] = Some((new_cost, node));
+            }
+        }
+    }
+
+    let Some((cost, _)) = best[to as usize] else {
+        return Ok(None);
+    };
+
+    let mut path = vec![to];
+    while *path.last().unwrap() != from {
+        path.push(best[*path.last().unwrap() as usize].unwrap().1);
+    }
+    path.reverse();
+
+    Ok(Some(SearchResult { cost, path }))
+}
+
+fn reachable<N, E>(graph: &Graph<N, E>, from: u32) -> Vec<u32> {
+    let mut seen = vec![false; graph.len()];
+    seen[from as usize] = true;
+
+    let mut stack = vec![from];
+    let mut nodes = vec![];
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        for next in graph.neighbours(node) {
+            if !seen[next as usize] {
+                seen[next as usize] = true;
+                stack.push(next);
+            }
+        }
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(input: &str) -> (Graph<(), ()>, impl Fn(&str) -> u32 + '_) {
+        let graph = Graph::parse_adjacency(input).unwrap();
+        let ids = graph.clone();
+        (graph, move |name| ids.id(name).unwrap())
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let (graph, id) = graph("a: b c\nb: d\nc: d\nd: e");
+        let order = topological_sort(&graph).unwrap();
+        let position = |n| order.iter().position(|&o| o == id(n)).unwrap();
+
+        assert_eq!(5, order.len());
+        assert!(position("a") < position("b"));
+        assert!(position("b") < position("d"));
+        assert!(position("c") < position("d"));
+        assert!(position("d") < position("e"));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let (graph, id) = graph("a: b\nb: c\nc: d\nd: b");
+        assert_eq!(
+            Err(Cycle {
+                nodes: vec![id("c"), id("d"), id("b")]
+            }),
+            topological_sort(&graph)
+        );
+
+        // The cycle is broken when "d" is left out
+        let subset = [id("c"), id("b"), id("a")];
+        assert_eq!(
+            Ok(vec![id("a"), id("b"), id("c")]),
+            topological_sort_of(&graph, &subset)
+        );
+    }
+
+    #[test]
+    fn test_is_consistent() {
+        let (graph, id) = graph("a: b c\nb: c");
+        assert!(is_consistent(&graph, &[id("a"), id("b"), id("c")]));
+        assert!(is_consistent(&graph, &[id("a"), id("c")]));
+        assert!(!is_consistent(&graph, &[id("c"), id("b")]));
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let (graph, id) = graph("s: a b\na: c\nb: c\nc: t x\nx: t");
+        assert_eq!(Ok(4u64), count_paths(&graph, id("s"), id("t"), &[]));
+        assert_eq!(Ok(2u64), count_paths(&graph, id("s"), id("t"), &[id("x")]));
+        assert_eq!(
+            Ok(1u128),
+            count_paths(&graph, id("s"), id("t"), &[id("x"), id("a")])
+        );
+        assert_eq!(Ok(0u64), count_paths(&graph, id("a"), id("b"), &[]));
+    }
+
+    #[test]
+    fn test_longest_and_shortest_path() {
+        let (graph, id) = graph("s: a b\na: c\nb: c\nc: t x\nx: t");
+        let longest = longest_path(&graph, id("s"), id("t"), |_| 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(4, longest.cost);
+        assert_eq!(id("x"), longest.path[3]);
+
+        let shortest = shortest_path(&graph, id("s"), id("t"), |_| 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(3, shortest.cost);
+        assert_eq!(4, shortest.path.len());
+        assert_eq!(Some(&id("t")), shortest.path.last());
+
+        assert_eq!(Ok(None), shortest_path(&graph, id("t"), id("s"), |_| 1));
+    }
+}
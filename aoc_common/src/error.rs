@@ -0,0 +1,101 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{params::ParamError, parse::ParseError};
+
+/// Why a solution could not give an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// The input does not have the expected format.
+    Parse(ParseError),
+    /// The whole search space was explored without finding an answer.
+    NoSolution,
+    /// The input breaks something the solution relies on, like a graph without cycles.
+    Assumption(String),
+    /// A puzzle parameter is unknown or has an invalid value.
+    Param(ParamError),
+}
+
+pub type AocResult<T> = Result<T, AocError>;
+
+impl AocError {
+    pub fn assumption(message: impl Into<String>) -> Self {
+        Self::Assumption(message.into())
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Parse(e) => write!(f, "invalid input at {}", e),
+            AocError::NoSolution => write!(f, "no solution found"),
+            AocError::Assumption(message) => write!(f, "assumption violated: {}", message),
+            AocError::Param(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Parse(e) => Some(e),
+            AocError::Param(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+impl From<ParamError> for AocError {
+    fn from(value: ParamError) -> Self {
+        Self::Param(value)
+    }
+}
+
+/// What a solution can return: a `Result`, or an `Option` where `None` means
+/// there is no solution.
+pub trait Answer<T> {
+    fn into_result(self) -> AocResult<T>;
+}
+
+impl<T> Answer<T> for AocResult<T> {
+    fn into_result(self) -> AocResult<T> {
+        self
+    }
+}
+
+impl<T> Answer<T> for Option<T> {
+    fn into_result(self) -> AocResult<T> {
+        self.ok_or(AocError::NoSolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Cursor;
+
+    #[test]
+    fn test_display() {
+        let error: AocError = Cursor::new("x").integer::<u32>().unwrap_err().into();
+        assert_eq!(
+            "invalid input at line 1, column 1: Expected an integer, found \"x\"",
+            error.to_string()
+        );
+        assert_eq!(
+            "assumption violated: no cycles",
+            AocError::assumption("no cycles").to_string()
+        );
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Err(AocError::NoSolution), None::<u32>.into_result());
+        assert_eq!(Ok(3), Some(3).into_result());
+        assert_eq!(Ok(3), Ok::<_, AocError>(3).into_result());
+    }
+}
@@ -0,0 +1,547 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::{Add, Sub},
+};
+
+use crate::parse::{Cursor, ParseResult};
+
+/// Maps names to small consecutive ids (`0..len()`) and back.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    /// Returns the id of `name`, assigning the next free one if it's new.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Directed graph with named nodes, interned to `u32` ids.
+/// Every node carries a value of type `N` and every edge a value of type `E`.
+/// Outgoing edges keep their insertion order, so e.g. "left" and "right" can be
+/// told apart by position.
+#[derive(Debug, Clone)]
+pub struct Graph<N, E> {
+    names: Interner,
+    nodes: Vec<N>,
+    successors: Vec<Vec<(u32, E)>>,
+    predecessors: Vec<Vec<(u32, E)>>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self {
+            names: Interner::default(),
+            nodes: vec![],
+            successors: vec![],
+            predecessors: vec![],
+        }
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Default,
+    E: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a graph with one node per line. `parse` returns the name of the
+    /// node, its value and its outgoing edges; target nodes that never appear
+    /// on their own line get a default value.
+    pub fn from_lines<'a>(
+        input: &'a str,
+        mut parse: impl FnMut(&'a str) -> (&'a str, N, Vec<(&'a str, E)>),
+    ) -> Self {
+        let mut graph = Self::new();
+        for line in input.lines().filter(|l| !l.trim().is_empty()) {
+            let (name, value, edges) = parse(line);
+            let id = graph.add_node(name);
+            graph.nodes[id as usize] = value;
+            for (target, edge) in edges {
+                let target = graph.add_node(target);
+                graph.add_edge(id, target, edge);
+            }
+        }
+        graph
+    }
+
+    /// Returns the id of the node called `name`, adding it if it doesn't exist yet.
+    pub fn add_node(&mut self, name: &str) -> u32 {
+        let id = self.names.intern(name);
+        if id as usize == self.nodes.len() {
+            self.nodes.push(N::default());
+            self.successors.push(vec![]);
+            self.predecessors.push(vec![]);
+        }
+        id
+    }
+
+    pub fn add_edge(&mut self, from: u32, to: u32, edge: E) {
+        self.successors[from as usize].push((to, edge.clone()));
+        self.predecessors[to as usize].push((from, edge));
+    }
+}
+
+impl Graph<(), ()> {
+    /// Parses the common "node: a b c" and "AAA = (BBB, CCC)" formats: the first
+    /// word of each line is the node, every following word is a successor.
+    pub fn parse_adjacency(input: &str) -> ParseResult<Self> {
+        let mut graph = Self::new();
+        let mut cursor = Cursor::new(input);
+        cursor.skip_whitespace();
+        while !cursor.is_empty() {
+            let id = graph.add_node(cursor.word()?);
+            let targets = cursor
+                .line()
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|w| !w.is_empty());
+            for target in targets {
+                let target = graph.add_node(target);
+                graph.add_edge(id, target, ());
+            }
+            cursor.skip_whitespace();
+        }
+        Ok(graph)
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = u32> {
+        0..self.nodes.len() as u32
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.names.id(name)
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        self.names.name(id)
+    }
+
+    pub fn node(&self, id: u32) -> &N {
+        &self.nodes[id as usize]
+    }
+
+    pub fn node_mut(&mut self, id: u32) -> &mut N {
+        &mut self.nodes[id as usize]
+    }
+
+    /// Outgoing edges of `id`, in insertion order.
+    pub fn successors(&self, id: u32) -> &[(u32, E)] {
+        &self.successors[id as usize]
+    }
+
+    /// Incoming edges of `id`, i.e. the reverse adjacency.
+    pub fn predecessors(&self, id: u32) -> &[(u32, E)] {
+        &self.predecessors[id as usize]
+    }
+
+    pub fn neighbours(&self, id: u32) -> impl Iterator<Item = u32> + '_ {
+        self.successors(id).iter().map(|&(n, _)| n)
+    }
+}
+
+/// Dense matrix of the shortest distances between a subset of "interesting"
+/// nodes of a graph. The nodes are interned to indices `0..len()`, in the order
+/// they were given, so they can be used directly as bits of a mask.
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix<S, C> {
+    nodes: Vec<S>,
+    indices: HashMap<S, usize>,
+    distances: Vec<Option<C>>,
+}
+
+impl<S, C> DistanceMatrix<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + From<u8> + Add<Output = C> + Sub<Output = C>,
+{
+    /// Computes the distances between every pair of `nodes`, exploring the graph
+    /// through `successors`. Depending on the graph it runs a BFS per node (unit
+    /// costs), Floyd–Warshall (small graphs), or Johnson's algorithm.
+    ///
+    /// # Panics
+    ///
+    /// If the explored graph contains a negative cycle.
+    pub fn new<I>(nodes: impl IntoIterator<Item = S>, successors: impl FnMut(&S) -> I) -> Self
+    where
+        I: IntoIterator<Item = (S, C)>,
+    {
+        let nodes: Vec<S> = nodes.into_iter().collect();
+        let graph = IndexedGraph::explore(&nodes, successors);
+
+        let sources: Vec<usize> = nodes.iter().map(|n| graph.indices[n]).collect();
+        let all_distances = if graph.edges().all(|(_, _, c)| c == C::from(1)) {
+            sources.iter().map(|&s| graph.bfs(s)).collect()
+        } else if graph.prefers_floyd_warshall(sources.len()) {
+            let all = graph.floyd_warshall();
+            sources.iter().map(|&s| all[s].clone()).collect()
+        } else {
+            graph.johnson(&sources)
+        };
+
+        let distances = all_distances
+            .iter()
+            .flat_map(|from: &Vec<Option<C>>| sources.iter().map(|&to| from[to]))
+            .collect();
+
+        Self {
+            indices: nodes.iter().cloned().zip(0..).collect(),
+            nodes,
+            distances,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn index(&self, node: &S) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &S {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[S] {
+        &self.nodes
+    }
+
+    /// Distance between the nodes with the given indices, `None` if unreachable.
+    pub fn get(&self, from: usize, to: usize) -> Option<C> {
+        self.distances[from * self.nodes.len() + to]
+    }
+
+    /// Distance between two of the interesting nodes, `None` if unreachable.
+    pub fn distance(&self, from: &S, to: &S) -> Option<C> {
+        self.get(self.index(from)?, self.index(to)?)
+    }
+}
+
+// Graph reachable from the interesting nodes, with states interned to indices
+struct IndexedGraph<S, C> {
+    indices: HashMap<S, usize>,
+    adjacency: Vec<Vec<(usize, C)>>,
+}
+
+impl<S, C> IndexedGraph<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + From<u8> + Add<Output = C> + Sub<Output = C>,
+{
+    fn explore<I>(nodes: &[S], mut successors: impl FnMut(&S) -> I) -> Self
+    where
+        I: IntoIterator<Item = (S, C)>,
+    {
+        let mut states: Vec<S> = vec![];
+        let mut indices = HashMap::new();
+        for node in nodes {
+            if let Entry::Vacant(entry) = indices.entry(node.clone()) {
+                entry.insert(states.len());
+                states.push(node.clone());
+            }
+        }
+
+        // States are discovered in index order, so the adjacency list grows along the queue
+        let mut adjacency = vec![];
+        let mut queue: VecDeque<usize> = (0..states.len()).collect();
+        while let Some(idx) = queue.pop_front() {
+            let edges = successors(&states[idx])
+                .into_iter()
+                .map(|(next, cost)| {
+                    let next_idx = *indices.entry(next.clone()).or_insert_with(|| {
+                        states.push(next);
+                        queue.push_back(states.len() - 1);
+                        states.len() - 1
+                    });
+                    (next_idx, cost)
+                })
+                .collect();
+            adjacency.push(edges);
+        }
+
+        Self { indices, adjacency }
+    }
+
+    fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (usize, usize, C)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, cost)| (from, to, cost)))
+    }
+
+    // Floyd–Warshall is O(V³), repeated Dijkstra is roughly O(k·E·log V)
+    fn prefers_floyd_warshall(&self, sources: usize) -> bool {
+        let v = self.len() as f64;
+        let e = self.edges().count() as f64;
+        v * v * v <= sources as f64 * (e + v) * v.log2().max(1.0)
+    }
+
+    fn bfs(&self, source: usize) -> Vec<Option<C>> {
+        let mut distances = vec![None; self.len()];
+        distances[source] = Some(C::default());
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(idx) = queue.pop_front() {
+            let next_distance = distances[idx].map(|d| d + C::from(1));
+            for &(next, _) in &self.adjacency[idx] {
+                if distances[next].is_none() {
+                    distances[next] = next_distance;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn floyd_warshall(&self) -> Vec<Vec<Option<C>>> {
+        let mut distances = vec![vec![None; self.len()]; self.len()];
+        for (idx, row) in distances.iter_mut().enumerate() {
+            row[idx] = Some(C::default());
+        }
+        for (from, to, cost) in self.edges() {
+            distances[from][to] = Some(distances[from][to].map_or(cost, |d: C| d.min(cost)));
+        }
+
+        for k in 0..self.len() {
+            let from_k = distances[k].clone();
+            for row in distances.iter_mut() {
+                let Some(ik) = row[k] else { continue };
+                for (ij, kj) in row.iter_mut().zip(&from_k) {
+                    if let Some(kj) = kj {
+                        let through = ik + *kj;
+                        if ij.is_none_or(|d| through < d) {
+                            *ij = Some(through);
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(
+            (0..self.len()).all(|i| distances[i][i] == Some(C::default())),
+            "Negative cycle in graph"
+        );
+        distances
+    }
+
+    // Bellman-Ford potentials make every edge non negative, then Dijkstra from each source
+    fn johnson(&self, sources: &[usize]) -> Vec<Vec<Option<C>>> {
+        let mut potentials = vec![C::default(); self.len()];
+        if self.edges().any(|(_, _, c)| c < C::default()) {
+            for round in 0..=self.len() {
+                let mut changed = false;
+                for (from, to, cost) in self.edges() {
+                    if potentials[from] + cost < potentials[to] {
+                        potentials[to] = potentials[from] + cost;
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+                assert!(round < self.len(), "Negative cycle in graph");
+            }
+        }
+
+        sources
+            .iter()
+            .map(|&source| {
+                self.dijkstra(source, &potentials)
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, d)| d.map(|d| d + potentials[idx] - potentials[source]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Distances are relative to the potentials, the caller converts them back
+    fn dijkstra(&self, source: usize, potentials: &[C]) -> Vec<Option<C>> {
+        let mut distances = vec![None; self.len()];
+        distances[source] = Some(C::default());
+
+        let mut heap = BinaryHeap::from([Reverse((C::default(), source))]);
+        while let Some(Reverse((cost, idx))) = heap.pop() {
+            if distances[idx].is_some_and(|d| cost > d) {
+                continue;
+            }
+            for &(next, edge) in &self.adjacency[idx] {
+                let new_cost = cost + potentials[idx] + edge - potentials[next];
+                if distances[next].is_none_or(|d| new_cost < d) {
+                    distances[next] = Some(new_cost);
+                    heap.push(Reverse((new_cost, next)));
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_distances() {
+        // Chain a - b - c - d, only a, c and d are interesting
+        let matrix = DistanceMatrix::<char, u32>::new(['a', 'c', 'd'], |n| match n {
+            'a' => vec![('b', 1)],
+            'b' => vec![('a', 1), ('c', 1)],
+            'c' => vec![('b', 1), ('d', 1)],
+            _ => vec![('c', 1)],
+        });
+
+        assert_eq!(3, matrix.len());
+        assert_eq!(Some(1), matrix.index(&'c'));
+        assert_eq!(None, matrix.index(&'b'));
+        assert_eq!(Some(2), matrix.distance(&'a', &'c'));
+        assert_eq!(Some(3), matrix.distance(&'d', &'a'));
+        assert_eq!(Some(0), matrix.get(2, 2));
+    }
+
+    #[test]
+    fn test_weighted_distances() {
+        let matrix = DistanceMatrix::<u8, u32>::new([0, 3], |n| match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 2)],
+            2 => vec![(3, 3)],
+            _ => vec![],
+        });
+
+        assert_eq!(Some(6), matrix.distance(&0, &3));
+        assert_eq!(None, matrix.distance(&3, &0));
+    }
+
+    #[test]
+    fn test_johnson_matches_floyd_warshall() {
+        let graph = IndexedGraph::<u8, i64>::explore(&[0], |n| match n {
+            0 => vec![(1, 4), (2, 2)],
+            1 => vec![(3, -3)],
+            2 => vec![(1, 1), (3, 5)],
+            _ => vec![],
+        });
+
+        let sources: Vec<usize> = (0..graph.len()).collect();
+        assert_eq!(graph.floyd_warshall(), graph.johnson(&sources));
+        assert_eq!(Some(0), graph.johnson(&[0])[0][graph.indices[&3]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Negative cycle")]
+    fn test_negative_cycle() {
+        let graph = IndexedGraph::<u8, i64>::explore(&[0], |n| match n {
+            0 => vec![(1, 1)],
+            _ => vec![(0, -2)],
+        });
+        graph.johnson(&[0]);
+    }
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::default();
+        assert_eq!(0, interner.intern("aaa"));
+        assert_eq!(1, interner.intern("bbb"));
+        assert_eq!(0, interner.intern("aaa"));
+        assert_eq!(Some(1), interner.id("bbb"));
+        assert_eq!(None, interner.id("ccc"));
+        assert_eq!("bbb", interner.name(1));
+        assert_eq!(2, interner.len());
+    }
+
+    #[test]
+    fn test_parse_adjacency() {
+        let graph = Graph::parse_adjacency("you: bbb ccc\nbbb: out\nccc: bbb out").unwrap();
+        let id = |name| graph.id(name).unwrap();
+
+        assert_eq!(4, graph.len());
+        assert_eq!(
+            vec![id("bbb"), id("ccc")],
+            graph.neighbours(id("you")).collect::<Vec<_>>()
+        );
+        assert!(graph.successors(id("out")).is_empty());
+        assert_eq!(
+            vec![id("bbb"), id("ccc")],
+            graph
+                .predecessors(id("out"))
+                .iter()
+                .map(|&(n, _)| n)
+                .collect::<Vec<_>>()
+        );
+
+        let graph = Graph::parse_adjacency("AAA = (BBB, CCC)\nBBB = (AAA, ZZZ)").unwrap();
+        assert_eq!("ZZZ", graph.name(graph.successors(1)[1].0));
+
+        let error = Graph::parse_adjacency("aaa: bbb\n\n: ccc").unwrap_err();
+        assert_eq!((3, 1), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC\nValve BB has flow rate=13; tunnel leads to valve AA";
+        let graph: Graph<u32, u32> = Graph::from_lines(input, |line| {
+            let words: Vec<&str> = line.split([' ', '=', ';', ',']).collect();
+            let edges = words[11..]
+                .iter()
+                .filter(|w| !w.is_empty())
+                .map(|&w| (w, 1))
+                .collect();
+            (words[1], words[5].parse().unwrap(), edges)
+        });
+
+        let bb = graph.id("BB").unwrap();
+        assert_eq!(3, graph.len());
+        assert_eq!(13, *graph.node(bb));
+        assert_eq!(0, *graph.node(graph.id("CC").unwrap()));
+        assert_eq!(&[(0, 1)], graph.successors(bb));
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    cmp::{max, min},
+    fmt::Debug,
+    ops::{Add, Sub},
+};
+
+/// Integer types usable as interval bounds.
+pub trait Bound: Copy + Ord + Debug + From<u8> + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T> Bound for T where T: Copy + Ord + Debug + From<u8> + Add<Output = T> + Sub<Output = T> {}
+
+/// Half-open interval `[start, end)`, empty when `start >= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Interval `[low, high]`, with both ends included.
+    pub fn inclusive(low: T, high: T) -> Self {
+        Self::new(low, high + T::from(1))
+    }
+
+    pub fn with_len(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::from(0)
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Last value inside the interval, the `high` of an inclusive interval.
+    pub fn last(&self) -> T {
+        self.end - T::from(1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(max(self.start, other.start), min(self.end, other.end));
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// Parts of `self` not covered by `other`, at most two intervals.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        [
+            Self::new(self.start, min(self.end, other.start)),
+            Self::new(max(self.start, other.end), self.end),
+        ]
+        .into_iter()
+        .filter(|i| !i.is_empty())
+        .collect()
+    }
+}
+
+/// Set of values stored as sorted, disjoint and non adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Intervals overlapping or touching the new one get merged into it
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+
+        let mut merged = interval;
+        if first < last {
+            merged.start = min(merged.start, self.intervals[first].start);
+            merged.end = max(merged.end, self.intervals[last - 1].end);
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|i| i.end <= interval.start);
+        let last = self.intervals.partition_point(|i| i.start < interval.end);
+
+        let remaining: Vec<Interval<T>> = self.intervals[first..last]
+            .iter()
+            .flat_map(|i| i.difference(&interval))
+            .collect();
+        self.intervals.splice(first..last, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(common) = a.intersection(&b) {
+                intersection.intervals.push(common);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        intersection
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for interval in &other.intervals {
+            difference.remove(*interval);
+        }
+        difference
+    }
+
+    /// Values inside `bounds` not in the set.
+    pub fn complement(&self, bounds: Interval<T>) -> Self {
+        let mut complement = Self::new();
+        complement.insert(bounds);
+        complement.difference(self)
+    }
+
+    /// Holes between the first and the last interval of the set.
+    pub fn gaps(&self) -> Vec<Interval<T>> {
+        self.intervals
+            .windows(2)
+            .map(|w| Interval::new(w[0].end, w[1].start))
+            .collect()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::from(0), |acc, i| acc + i.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// Interval of the set containing `value`.
+    pub fn find(&self, value: T) -> Option<&Interval<T>> {
+        let idx = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(idx).filter(|i| i.contains(value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Piecewise-linear map, shifting every source interval onto a destination.
+/// Values outside all the source intervals are mapped to themselves.
+#[derive(Debug, Clone, Default)]
+pub struct RangeMap<T> {
+    // Source intervals, sorted by start, with the destination of their start
+    ranges: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Bound> RangeMap<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Maps `source` onto the interval of the same length starting at `destination`.
+    /// Source intervals are expected not to overlap.
+    pub fn insert(&mut self, source: Interval<T>, destination: T) {
+        let idx = self.ranges.partition_point(|(s, _)| s.start < source.start);
+        self.ranges.insert(idx, (source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let idx = self.ranges.partition_point(|(s, _)| s.end <= value);
+        match self.ranges.get(idx) {
+            Some((source, destination)) if source.contains(value) => {
+                *destination + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// Image of a whole interval, which can be split across several ranges.
+    pub fn map_interval(&self, interval: Interval<T>) -> IntervalSet<T> {
+        let mut image = IntervalSet::new();
+        let mut unmapped = IntervalSet::from_iter([interval]);
+
+        for (source, destination) in &self.ranges {
+            if let Some(common) = source.intersection(&interval) {
+                image.insert(Interval::with_len(
+                    *destination + (common.start - source.start),
+                    common.len(),
+                ));
+                unmapped.remove(common);
+            }
+        }
+
+        image.union(&unmapped)
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter().fold(IntervalSet::new(), |acc, i| {
+            acc.union(&self.map_interval(*i))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(s, e)| Interval::new(s, e))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::inclusive(3u64, 7);
+        assert_eq!(Interval::new(3, 8), interval);
+        assert_eq!(5, interval.len());
+        assert_eq!(7, interval.last());
+        assert!(interval.contains(7));
+        assert!(!interval.contains(8));
+
+        assert_eq!(
+            Some(Interval::new(5, 8)),
+            interval.intersection(&Interval::new(5, 20))
+        );
+        assert_eq!(None, interval.intersection(&Interval::new(8, 20)));
+        assert_eq!(
+            vec![Interval::new(3, 4), Interval::new(6, 8)],
+            interval.difference(&Interval::new(4, 6))
+        );
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut intervals = set(&[(10, 20), (0, 5), (30, 40)]);
+        assert_eq!(set(&[(0, 5), (10, 20), (30, 40)]), intervals);
+
+        // Adjacent intervals are merged as well
+        intervals.insert(Interval::new(5, 10));
+        assert_eq!(set(&[(0, 20), (30, 40)]), intervals);
+
+        intervals.insert(Interval::new(15, 35));
+        assert_eq!(set(&[(0, 40)]), intervals);
+        assert_eq!(40, intervals.len());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut intervals = set(&[(0, 10), (20, 30)]);
+        intervals.remove(Interval::new(5, 25));
+        assert_eq!(set(&[(0, 5), (25, 30)]), intervals);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+
+        assert_eq!(set(&[(0, 30)]), a.union(&b));
+        assert_eq!(set(&[(5, 10), (20, 25)]), a.intersection(&b));
+        assert_eq!(set(&[(0, 5), (25, 30)]), a.difference(&b));
+        assert_eq!(
+            set(&[(-5, 0), (10, 20), (30, 35)]),
+            a.complement(Interval::new(-5, 35))
+        );
+        assert_eq!(vec![Interval::new(10, 20)], a.gaps());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let intervals = set(&[(0, 10), (20, 30)]);
+        assert!(intervals.contains(0));
+        assert!(!intervals.contains(10));
+        assert_eq!(Some(&Interval::new(20, 30)), intervals.find(25));
+        assert_eq!(None, intervals.find(-1));
+    }
+
+    #[test]
+    fn test_range_map() {
+        let mut map = RangeMap::new();
+        map.insert(Interval::with_len(98u64, 2), 50);
+        map.insert(Interval::with_len(50, 48), 52);
+
+        assert_eq!(81, map.map(79));
+        assert_eq!(50, map.map(98));
+        assert_eq!(10, map.map(10));
+
+        let image = map.map_interval(Interval::new(45, 100));
+        assert_eq!(IntervalSet::from_iter([Interval::new(45, 100)]), image);
+        assert_eq!(
+            IntervalSet::from_iter([Interval::new(50, 51), Interval::new(99, 100)]),
+            map.map_set(&IntervalSet::from_iter([Interval::new(97, 99)]))
+        );
+    }
+}
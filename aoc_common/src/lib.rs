@@ -0,0 +1,14 @@
+//! Modules shared by the solutions of every year, re-exported by each year's
+//! `advent_of_code` crate.
+
+pub mod assumptions;
+pub mod cycle;
+pub mod dag;
+pub mod error;
+pub mod graph;
+pub mod interval;
+pub mod math;
+pub mod params;
+pub mod parse;
+pub mod search;
+pub mod vm;
@@ -0,0 +1,243 @@
+//! Number theory and exact linear algebra on integers.
+
+/// Greatest common divisor, always positive (or 0 when both are 0).
+pub fn gcd(a: i64, b: i64) -> i64 {
+    extended_gcd(a, b).0
+}
+
+/// Same as [gcd] on 128 bits, for values which grow like the rows of an
+/// elimination.
+pub fn gcd_wide(a: i128, b: i128) -> i128 {
+    extended_gcd_wide(a, b).0
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g` (Bézout coefficients).
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x = 1 (mod m)`, when `a` and `m` are coprime and
+/// `m` is positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base ^ exp (mod m)` by squaring, without overflowing for any `m`. `None`
+/// when `m` is not positive.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    Some(result as i64)
+}
+
+/// Least common multiple, `None` on overflow.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    let g = extended_gcd_wide(a as i128, b as i128).0 as u64;
+    (a / g).checked_mul(b)
+}
+
+/// Least common multiple of all the values (1 for none), `None` on overflow.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// Chinese Remainder Theorem: the smallest `x >= 0` with `x = residue (mod modulus)`
+/// for every `(residue, modulus)`, with the LCM of the moduli every solution is
+/// congruent modulo. The moduli do not have to be coprime, `None` when the
+/// congruences contradict each other, when a modulus is not positive or when
+/// the LCM overflows.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut modulus: i128 = 1;
+    for &(r, m) in congruences {
+        if m <= 0 {
+            return None;
+        }
+        let (r, m) = (r as i128, m as i128);
+        let (g, p, _) = extended_gcd_wide(modulus, m);
+        if (r - x) % g != 0 {
+            return None;
+        }
+
+        // x + modulus * k = r (mod m), so k = (r - x) / g * p (mod m / g)
+        let step = m / g;
+        let k = ((r - x) / g % step * p).rem_euclid(step);
+        let lcm = modulus.checked_mul(step)?;
+        x = (x + modulus * k).rem_euclid(lcm);
+        modulus = lcm;
+    }
+    Some((i64::try_from(x).ok()?, i64::try_from(modulus).ok()?))
+}
+
+/// Determinant of a square matrix, with Bareiss' algorithm which only does exact
+/// integer divisions.
+pub fn determinant(matrix: &[Vec<i64>]) -> i128 {
+    let n = matrix.len();
+    let mut m: Vec<Vec<i128>> = matrix
+        .iter()
+        .map(|row| row.iter().map(|&v| v as i128).collect())
+        .collect();
+
+    let mut sign = 1;
+    let mut previous = 1;
+    for k in 0..n {
+        if m[k][k] == 0 {
+            match (k + 1..n).find(|&i| m[i][k] != 0) {
+                Some(i) => {
+                    m.swap(k, i);
+                    sign = -sign;
+                }
+                None => return 0,
+            }
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                m[i][j] = (m[i][j] * m[k][k] - m[i][k] * m[k][j]) / previous;
+            }
+        }
+        previous = m[k][k];
+    }
+
+    if n == 0 {
+        1
+    } else {
+        sign * m[n - 1][n - 1]
+    }
+}
+
+/// The integer solution of `matrix * x = rhs` (with Cramer's rule), `None` when
+/// there is no unique solution or it is not made of integers.
+pub fn solve_integer(matrix: &[Vec<i64>], rhs: &[i64]) -> Option<Vec<i64>> {
+    let det = determinant(matrix);
+    if det == 0 {
+        return None;
+    }
+
+    (0..matrix.len())
+        .map(|col| {
+            let replaced: Vec<Vec<i64>> = matrix
+                .iter()
+                .zip(rhs)
+                .map(|(row, &value)| {
+                    let mut row = row.clone();
+                    row[col] = value;
+                    row
+                })
+                .collect();
+            let numerator = determinant(&replaced);
+            if numerator % det != 0 {
+                return None;
+            }
+            i64::try_from(numerator / det).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(6, gcd(12, 18));
+        assert_eq!(6, gcd(-12, 18));
+        assert_eq!(5, gcd(0, 5));
+        assert_eq!(1 << 70, gcd_wide(3 << 70, -(1 << 71)));
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(2, g);
+        assert_eq!(g, 240 * x + 46 * y);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(Some(7), mod_inverse(-3, 11));
+        assert_eq!(None, mod_inverse(2, 4));
+        assert_eq!(None, mod_inverse(3, 0));
+        assert_eq!(Some(445), mod_pow(4, 13, 497));
+        assert_eq!(Some(1), mod_pow(i64::MAX - 1, u64::MAX - 1, i64::MAX));
+        assert_eq!(Some(0), mod_pow(5, 0, 1));
+        assert_eq!(None, mod_pow(5, 3, 0));
+        assert_eq!(None, mod_pow(5, 3, -7));
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(Some(96577), lcm_all([23, 19, 13, 17]));
+        assert_eq!(Some(12), lcm_all([4, 6]));
+        assert_eq!(Some(1), lcm_all([]));
+        assert_eq!(None, lcm_all([u64::MAX, u64::MAX - 1]));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        // Non coprime moduli
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1, 6), (2, 4)]));
+        assert_eq!(Some((0, 1)), crt(&[]));
+        assert_eq!(None, crt(&[(1, 3), (0, 0)]));
+        assert_eq!(None, crt(&[(1, -3)]));
+        let moduli = [i64::MAX, i64::MAX - 1, i64::MAX - 2];
+        assert_eq!(None, crt(&moduli.map(|m| (0, m))));
+    }
+
+    #[test]
+    fn test_solve_integer() {
+        assert_eq!(-2, determinant(&[vec![1, 2], vec![3, 4]]));
+        assert_eq!(
+            -306,
+            determinant(&[vec![6, 1, 1], vec![4, -2, 5], vec![2, 8, 7]])
+        );
+        assert_eq!(0, determinant(&[vec![1, 2], vec![2, 4]]));
+        assert_eq!(5, determinant(&[vec![0, 1], vec![-5, 3]]));
+
+        assert_eq!(
+            Some(vec![80, 40]),
+            solve_integer(&[vec![94, 22], vec![34, 67]], &[8400, 5400])
+        );
+        assert_eq!(
+            None,
+            solve_integer(&[vec![26, 67], vec![66, 21]], &[12748, 12176])
+        );
+        assert_eq!(None, solve_integer(&[vec![1, 2], vec![2, 4]], &[3, 6]));
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+/// Puzzle constants which differ between the examples and the real input,
+/// like the row to scan or the size of the grid.
+/// Solutions read them with the real input value as default, tests and the
+/// command line (`--param row=10`) override them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+/// Why the parameters given on the command line cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// An argument which is not a `key=value` pair.
+    Malformed(String),
+    /// A key the day does not read, most likely misspelt.
+    UnknownKey(String),
+    /// A key given to a whole year which does not start with its day.
+    Unscoped(String),
+    /// A value which cannot be parsed as the type of its key.
+    InvalidValue { key: String, value: String },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Malformed(arg) => write!(f, "expected --param key=value, got {:?}", arg),
+            ParamError::UnknownKey(key) => write!(f, "unknown parameter {:?}", key),
+            ParamError::Unscoped(key) => {
+                write!(f, "expected --param day.key=value, got key {:?}", key)
+            }
+            ParamError::InvalidValue { key, value } => {
+                write!(f, "invalid value {:?} for parameter {:?}", value, key)
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parameters given on the command line as `--param key=value`, where
+    /// every key must be one of `keys`.
+    pub fn from_args(keys: &[&str]) -> Result<Self, ParamError> {
+        Self::parse(env::args().skip(1), keys)
+    }
+
+    /// Collects every `--param key=value` (or `--param=key=value`), ignoring
+    /// any other argument.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        keys: &[&str],
+    ) -> Result<Self, ParamError> {
+        let mut params = Self::new();
+        for (key, value) in pairs(args)? {
+            if !keys.contains(&key.as_str()) {
+                return Err(ParamError::UnknownKey(key));
+            }
+            params.values.insert(key, value);
+        }
+        Ok(params)
+    }
+
+    /// Parameters given to the runner of a whole year as `--param day.key=value`,
+    /// split by day with the day removed from their keys.
+    pub fn by_day(args: impl IntoIterator<Item = String>) -> Result<HashMap<u8, Self>, ParamError> {
+        let mut days: HashMap<u8, Self> = HashMap::new();
+        for (key, value) in pairs(args)? {
+            let scoped = key
+                .split_once('.')
+                .and_then(|(day, key)| Some((day.parse::<u8>().ok()?, key)))
+                .filter(|(day, _)| (1..=25).contains(day));
+            let Some((day, key)) = scoped else {
+                return Err(ParamError::Unscoped(key));
+            };
+            let params = days.entry(day).or_default();
+            params.values.insert(key.to_string(), value);
+        }
+        Ok(days)
+    }
+
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        self.values.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Value of `key`, or `default` when it was not overridden.
+    pub fn get<T: FromStr>(&self, key: &str, default: T) -> Result<T, ParamError> {
+        match self.values.get(key) {
+            Some(value) => value.parse().map_err(|_| ParamError::InvalidValue {
+                key: key.to_string(),
+                value: value.clone(),
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// Command line arguments giving the same parameters, to forward them.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut pairs: Vec<(&String, &String)> = self.values.iter().collect();
+        pairs.sort();
+        pairs
+            .into_iter()
+            .flat_map(|(key, value)| ["--param".to_string(), format!("{}={}", key, value)])
+            .collect()
+    }
+}
+
+/// The `key=value` pairs following every `--param`.
+fn pairs(args: impl IntoIterator<Item = String>) -> Result<Vec<(String, String)>, ParamError> {
+    let mut pairs = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let pair = if arg == "--param" {
+            args.next().unwrap_or_default()
+        } else if let Some(pair) = arg.strip_prefix("--param=") {
+            pair.to_string()
+        } else {
+            continue;
+        };
+        match pair.split_once('=') {
+            Some((key, value)) => pairs.push((key.to_string(), value.to_string())),
+            None => return Err(ParamError::Malformed(pair)),
+        }
+    }
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let params = Params::new().with("row", 10);
+        assert_eq!(Ok(10), params.get("row", 2000000));
+        assert_eq!(Ok(4000000), params.get("max_coord", 4000000));
+    }
+
+    #[test]
+    fn test_parse() {
+        let keys = ["row", "max"];
+        let params = Params::parse(args(&["15", "--param", "row=10", "--param=max=20"]), &keys);
+        let params = params.unwrap();
+        assert_eq!(Params::new().with("row", 10).with("max", 20), params);
+        assert_eq!(
+            args(&["--param", "max=20", "--param", "row=10"]),
+            params.to_args()
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            Err(ParamError::UnknownKey("rwo".into())),
+            Params::parse(args(&["--param", "rwo=10"]), &["row"])
+        );
+        assert_eq!(
+            Err(ParamError::Malformed("row".into())),
+            Params::parse(args(&["--param", "row"]), &["row"])
+        );
+        assert_eq!(
+            Err(ParamError::Malformed("".into())),
+            Params::parse(args(&["--param"]), &["row"])
+        );
+        assert_eq!(
+            Err(ParamError::InvalidValue {
+                key: "row".into(),
+                value: "ten".into()
+            }),
+            Params::new().with("row", "ten").get("row", 0)
+        );
+    }
+
+    #[test]
+    fn test_by_day() {
+        let days = Params::by_day(args(&[
+            "--param",
+            "15.row=10",
+            "--param=14.width=11",
+            "--param=15.max_coord=20",
+        ]))
+        .unwrap();
+        assert_eq!(2, days.len());
+        assert_eq!(Params::new().with("width", 11), days[&14]);
+        assert_eq!(
+            Params::new().with("row", 10).with("max_coord", 20),
+            days[&15]
+        );
+
+        for key in ["row", "26.row", "x.row"] {
+            assert_eq!(
+                Err(ParamError::Unscoped(key.into())),
+                Params::by_day(args(&["--param", &format!("{}=1", key)]))
+            );
+        }
+    }
+}
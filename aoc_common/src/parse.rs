@@ -0,0 +1,523 @@
+use std::{collections::HashMap, error::Error, fmt::Display, str::FromStr};
+
+/// Parsing failure, with the 1-based line and column where it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    /// Moves an error found in a text read from `start` to its place in the
+    /// whole input of `start`, as when each line is parsed on its own.
+    pub fn within(self, start: &Cursor) -> Self {
+        let (line, column) = start.position();
+        ParseError {
+            line: line + self.line - 1,
+            column: if self.line == 1 {
+                column + self.column - 1
+            } else {
+                self.column
+            },
+            message: self.message,
+        }
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// All the integers found in `text`, skipping any other text, so that lines like
+/// `Sensor at x=2, y=-18: closest beacon is at x=-2, y=15` can be read as they are.
+/// A `-` is a sign only when it does not follow a letter or a digit, to read
+/// ranges like `2-4` as two positive numbers. Fails on a number `T` cannot hold,
+/// like a negative one for an unsigned type.
+pub fn integers<T: FromStr>(text: &str) -> ParseResult<Vec<T>> {
+    let bytes = text.as_bytes();
+    let mut numbers = vec![];
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let signed = bytes[idx] == b'-'
+            && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+            && (idx == 0 || !bytes[idx - 1].is_ascii_alphanumeric());
+        if !signed && !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        idx += 1;
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+        let number = &text[start..idx];
+        let value = number.parse().map_err(|_| {
+            let cursor = Cursor {
+                input: text,
+                offset: start,
+            };
+            cursor.error(format!("Invalid integer {:?}", number))
+        })?;
+        numbers.push(value);
+    }
+    Ok(numbers)
+}
+
+/// Groups of lines separated by blank lines.
+pub fn blocks(text: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() {
+                blocks.push(text[s..end].trim_end());
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        blocks.push(text[s..end].trim_end());
+    }
+    blocks
+}
+
+/// Lines like `key: value`, skipping the blank ones. Fails when a key is given
+/// twice.
+pub fn key_values(text: &str) -> ParseResult<HashMap<&str, &str>> {
+    let mut cursor = Cursor::new(text);
+    let mut values = HashMap::new();
+    while !cursor.is_empty() {
+        let start = cursor;
+        let line = cursor.line();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| start.error(format!("Expected `key: value`, found {:?}", line)))?;
+        if values.insert(key.trim(), value.trim()).is_some() {
+            return Err(start.error(format!("Duplicate key {:?}", key.trim())));
+        }
+    }
+    Ok(values)
+}
+
+/// Comma separated values between any kind of brackets, like `(1,3)` or `{3,5}`.
+pub fn list<T: FromStr>(text: &str) -> ParseResult<Vec<T>> {
+    let mut cursor = Cursor::new(text.trim());
+    let (open, close) = match cursor.peek() {
+        Some('(') => ('(', ')'),
+        Some('[') => ('[', ']'),
+        Some('{') => ('{', '}'),
+        Some('<') => ('<', '>'),
+        _ => return Err(cursor.error("Expected an opening bracket")),
+    };
+    let values = cursor.list(open, close, |c| c.value())?;
+    cursor.end()?;
+    Ok(values)
+}
+
+/// Position inside a text being parsed, moving forward as values are read.
+/// Every failure is reported with the line and column where it happened.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, offset: 0 }
+    }
+
+    /// Text not parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Line and column (both 1-based) of the next character.
+    pub fn position(&self) -> (usize, usize) {
+        let before = &self.input[..self.offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+        (line, column)
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let (line, column) = self.position();
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Fails unless the whole input has been read (trailing whitespace aside).
+    pub fn end(&mut self) -> ParseResult<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(()),
+            Some(c) => Err(self.error(format!("Unexpected {:?}", c))),
+        }
+    }
+
+    pub fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.offset += len;
+        &rest[..len]
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// Skips spaces and tabs, but not the end of the line.
+    pub fn skip_spaces(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// Rest of the current line, moving to the start of the next one.
+    pub fn line(&mut self) -> &'a str {
+        let line = self.take_while(|c| c != '\n');
+        if self.peek() == Some('\n') {
+            self.offset += 1;
+        }
+        line.strip_suffix('\r').unwrap_or(line)
+    }
+
+    pub fn char(&mut self, expected: char) -> ParseResult<()> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("Expected {:?}, found {:?}", expected, c))),
+            None => Err(self.error(format!("Expected {:?}, found the end", expected))),
+        }
+    }
+
+    pub fn tag(&mut self, tag: &str) -> ParseResult<()> {
+        if self.rest().starts_with(tag) {
+            self.offset += tag.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected {:?}", tag)))
+        }
+    }
+
+    /// Letters, digits and underscores.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        let word = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if word.is_empty() {
+            Err(self.error("Expected a word"))
+        } else {
+            Ok(word)
+        }
+    }
+
+    /// Signed integer, with an optional `-` or `+` in front.
+    pub fn integer<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = *self;
+        let sign = match self.peek() {
+            Some(c @ ('-' | '+')) => c.len_utf8(),
+            _ => 0,
+        };
+        self.offset += sign;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        let number = &start.rest()[..sign + digits.len()];
+
+        match (digits.is_empty(), number.parse()) {
+            (false, Ok(value)) => Ok(value),
+            _ => {
+                *self = start;
+                Err(self.error(format!("Expected an integer, found {:?}", self.ahead())))
+            }
+        }
+    }
+
+    /// Any value up to the next separator, closing bracket or whitespace.
+    pub fn value<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = *self;
+        let text = self.take_while(|c| !c.is_whitespace() && !",;)]}>".contains(c));
+        text.parse().map_err(|_| {
+            let error = start.error(format!("Invalid value {:?}", text));
+            *self = start;
+            error
+        })
+    }
+
+    /// Text between `open` and `close`, like the `.##.` of `[.##.]`.
+    pub fn bracketed(&mut self, open: char, close: char) -> ParseResult<&'a str> {
+        self.char(open)?;
+        let inside = self.take_while(|c| c != close);
+        self.char(close)?;
+        Ok(inside)
+    }
+
+    /// Items separated by `separator` (not a whitespace) and optional spaces.
+    pub fn separated<T>(
+        &mut self,
+        separator: char,
+        mut item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+        loop {
+            self.skip_spaces();
+            if self.peek() != Some(separator) {
+                return Ok(items);
+            }
+            self.char(separator)?;
+            self.skip_spaces();
+            items.push(item(self)?);
+        }
+    }
+
+    /// Comma separated items between `open` and `close`, like `(1,3)`.
+    pub fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.char(open)?;
+        self.skip_spaces();
+        if self.peek() == Some(close) {
+            self.char(close)?;
+            return Ok(vec![]);
+        }
+        let items = self.separated(',', item)?;
+        self.char(close)?;
+        Ok(items)
+    }
+
+    // Start of the text not parsed yet, to show in errors
+    fn ahead(&self) -> String {
+        self.rest()
+            .chars()
+            .take_while(|&c| c != '\n')
+            .take(20)
+            .collect()
+    }
+
+    // The following are used by `#[derive(AocParse)]`, to read a text against its format.
+
+    /// Matches the literal text of a format, where any whitespace matches any
+    /// amount of whitespace in the input.
+    pub fn pattern(&mut self, pattern: &str) -> ParseResult<()> {
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            if c.is_whitespace() {
+                self.skip_whitespace();
+                rest = rest.trim_start();
+                continue;
+            }
+            if self.peek() != Some(c) {
+                return Err(self.error(format!("Expected {:?}, found {:?}", rest, self.ahead())));
+            }
+            self.offset += c.len_utf8();
+            rest = &rest[c.len_utf8()..];
+        }
+        Ok(())
+    }
+
+    /// Value of the field named `name`, followed by the `next` text of the format.
+    pub fn field<T>(&mut self, name: &str, next: &str) -> ParseResult<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.skip_spaces();
+        let start = *self;
+        let text = self.field_text(next);
+        text.parse()
+            .map_err(|e| start.error(format!("Invalid {} {:?}: {}", name, text, e)))
+    }
+
+    /// Values of the field named `name`, split by `separator`.
+    pub fn field_list<C, T>(&mut self, name: &str, separator: &str, next: &str) -> ParseResult<C>
+    where
+        C: FromIterator<T>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.skip_spaces();
+        let start = *self;
+        let text = self.field_text(next);
+        let items: Vec<&str> = if separator.trim().is_empty() {
+            text.split_whitespace().collect()
+        } else if text.is_empty() {
+            vec![]
+        } else {
+            text.split(separator).map(str::trim).collect()
+        };
+
+        items
+            .into_iter()
+            .map(|item| {
+                item.parse()
+                    .map_err(|e| start.error(format!("Invalid {} item {:?}: {}", name, item, e)))
+            })
+            .collect()
+    }
+
+    // A field ends where the whole next text of the format matches, so it can
+    // contain the start of that text. When it starts with a whitespace, the field
+    // ends at the next whitespace (or at the end of the line when it is a newline),
+    // and it never goes past the end of the line.
+    fn field_text(&mut self, next: &str) -> &'a str {
+        let rest = self.rest();
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let trimmed = next.trim_start();
+
+        let end = if next.is_empty() {
+            rest.len()
+        } else if next[..next.len() - trimmed.len()].contains('\n') {
+            line_end
+        } else if trimmed.len() < next.len() {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        } else {
+            rest[..line_end]
+                .char_indices()
+                .map(|(idx, _)| idx)
+                .find(|&idx| {
+                    let mut ahead = *self;
+                    ahead.offset += idx;
+                    ahead.pattern(next).is_ok()
+                })
+                .unwrap_or(rest.len())
+        };
+
+        self.offset += end.min(line_end);
+        rest[..end.min(line_end)].trim_end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(Ok(vec![2, -18, -2, 15]), integers::<i32>(line));
+        assert_eq!(Ok(vec![2, 4, 6, 8]), integers::<u32>("2-4,6-8"));
+        assert_eq!(Ok(vec![7, 1]), integers::<i64>("move 7 from -x1"));
+
+        let error = integers::<u32>("1 2\nx=-3").unwrap_err();
+        assert_eq!((2, 3), (error.line, error.column));
+        assert!(integers::<u8>("256").is_err());
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = "a\nb\n\n\nc\n  \nd\r\ne\n";
+        assert_eq!(vec!["a\nb", "c", "d\r\ne"], blocks(text));
+        assert!(blocks("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_key_values() {
+        let values = key_values("Register A: 729\n\nProgram: 0,1,5").unwrap();
+        assert_eq!(Some(&"729"), values.get("Register A"));
+        assert_eq!(Some(&"0,1,5"), values.get("Program"));
+
+        let error = key_values("A: 1\nB 2").unwrap_err();
+        assert_eq!((2, 1), (error.line, error.column));
+        let error = key_values("A: 1\nB: 2\nA: 3").unwrap_err();
+        assert_eq!((3, 1), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(Ok(vec![1, 3]), list::<u32>("(1,3)"));
+        assert_eq!(Ok(vec![3, 5, 4]), list::<u32>("{3, 5, 4}"));
+        assert_eq!(Ok(Vec::<u32>::new()), list::<u32>("[]"));
+        assert!(list::<u32>("(1,x)").is_err());
+
+        let mut cursor = Cursor::new("[.##.] (3) (1,3)");
+        assert_eq!(Ok(".##."), cursor.bracketed('[', ']'));
+        let mut buttons = vec![];
+        cursor.skip_spaces();
+        while cursor.peek() == Some('(') {
+            buttons.push(cursor.list('(', ')', Cursor::integer::<u8>).unwrap());
+            cursor.skip_spaces();
+        }
+        assert_eq!(vec![vec![3], vec![1, 3]], buttons);
+        assert_eq!(Ok(()), cursor.end());
+    }
+
+    #[test]
+    fn test_error_position() {
+        let mut cursor = Cursor::new("Register A: 729\nRegister B: x");
+        cursor.line();
+        cursor.tag("Register B: ").unwrap();
+        let error = cursor.integer::<u64>().unwrap_err();
+        assert_eq!((2, 13), (error.line, error.column));
+        assert_eq!(
+            "line 2, column 13: Expected an integer, found \"x\"",
+            error.to_string()
+        );
+
+        let mut cursor = Cursor::new("seeds: 79\nsoil: 14 300");
+        cursor.line();
+        cursor.tag("soil:").unwrap();
+        let error = integers::<u8>(cursor.rest()).unwrap_err().within(&cursor);
+        assert_eq!((2, 10), (error.line, error.column));
+        let error = integers::<u8>("1\n300").unwrap_err().within(&cursor);
+        assert_eq!((3, 1), (error.line, error.column));
+    }
+
+    #[test]
+    fn test_format_fields() {
+        let mut cursor = Cursor::new("Starting items: 79, 98\n  Operation: new = old * 19");
+        cursor.pattern("Starting items: ").unwrap();
+        let items: Vec<u64> = cursor.field_list("items", ",", "\nOperation: ").unwrap();
+        assert_eq!(vec![79, 98], items);
+        cursor.pattern("\nOperation: new = old ").unwrap();
+        assert_eq!(Ok('*'), cursor.field("operation", " "));
+        cursor.pattern(" ").unwrap();
+        assert_eq!(Ok(19), cursor.field::<u64>("operand", ""));
+        assert_eq!(Ok(()), cursor.end());
+
+        let mut cursor = Cursor::new("p=0,x v=3,-3");
+        cursor.pattern("p=").unwrap();
+        assert_eq!(Ok(0), cursor.field::<i32>("x", ","));
+        cursor.pattern(",").unwrap();
+        let error = cursor.field::<i32>("y", " v=").unwrap_err();
+        assert_eq!((1, 5), (error.line, error.column));
+        assert!(error.message.starts_with("Invalid y \"x\""));
+        assert!(cursor.pattern("p=").is_err());
+
+        // The field goes on until all of the next text matches
+        let mut cursor = Cursor::new("a: b: c=1");
+        assert_eq!(Ok("a: b".to_string()), cursor.field("name", ": c="));
+        cursor.pattern(": c=").unwrap();
+        assert_eq!(Ok(1), cursor.field::<u32>("c", ""));
+    }
+}
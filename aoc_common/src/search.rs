@@ -0,0 +1,473 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Result of a successful search: the cost to reach the goal and the
+/// states visited along the way (start and goal included).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+// Every discovered state is stored once, together with the index of the
+// state it was reached from and its best known cost. The queues only hold
+// indices into this list, so states don't need to be `Ord` or `Copy`.
+struct Visited<S, C> {
+    nodes: Vec<(S, usize, C)>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S, C> Visited<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy,
+{
+    fn new(start: S, cost: C) -> Self {
+        Self {
+            nodes: vec![(start.clone(), usize::MAX, cost)],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    fn path(&self, mut idx: usize) -> Vec<S> {
+        let mut path = vec![];
+        while idx != usize::MAX {
+            let (state, parent, _) = &self.nodes[idx];
+            path.push(state.clone());
+            idx = *parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn result(&self, idx: usize) -> SearchResult<S, C> {
+        SearchResult {
+            cost: self.nodes[idx].2,
+            path: self.path(idx),
+        }
+    }
+}
+
+/// Breadth first search from `start`, stopping at the first state matching `is_goal`.
+/// The cost is the number of steps taken.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, usize>>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(idx) = queue.pop_front() {
+        let (state, _, steps) = visited.nodes[idx].clone();
+        if is_goal(&state) {
+            return Some(visited.result(idx));
+        }
+
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = visited.indices.entry(next.clone()) {
+                entry.insert(visited.nodes.len());
+                queue.push_back(visited.nodes.len());
+                visited.nodes.push((next, idx, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra search from `start`, stopping at the first state matching `is_goal`.
+/// `successors` returns the reachable states together with the cost of the move.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search from `start`, stopping at the first state matching `is_goal`.
+/// The `heuristic` must never overestimate the remaining cost, otherwise the
+/// returned path may not be the cheapest one.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut visited = Visited::new(start, C::default());
+
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        let state = visited.nodes[idx].0.clone();
+        if is_goal(&state) {
+            return Some(visited.result(idx));
+        }
+
+        // Skip stale entries, a cheaper way to this state was already found
+        if cost > visited.nodes[idx].2 {
+            continue;
+        }
+
+        for (next, move_cost) in successors(&state) {
+            let new_cost = cost + move_cost;
+            let next_idx = match visited.indices.entry(next.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(visited.nodes.len());
+                    visited.nodes.push((next.clone(), idx, new_cost));
+                    visited.nodes.len() - 1
+                }
+                Entry::Occupied(entry) => {
+                    let next_idx = *entry.get();
+                    if new_cost >= visited.nodes[next_idx].2 {
+                        continue;
+                    }
+                    visited.nodes[next_idx] = (next.clone(), idx, new_cost);
+                    next_idx
+                }
+            };
+            heap.push(Reverse((new_cost + heuristic(&next), new_cost, next_idx)));
+        }
+    }
+
+    None
+}
+
+/// All the shortest paths from a start state to the cheapest goal states,
+/// stored as a DAG where every state keeps all its equal-cost predecessors.
+#[derive(Debug, Clone)]
+pub struct ShortestPathDag<S, C> {
+    cost: C,
+    start: usize,
+    goals: Vec<usize>,
+    states: Vec<S>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<S, C> ShortestPathDag<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy,
+{
+    pub fn cost(&self) -> C {
+        self.cost
+    }
+
+    pub fn goals(&self) -> impl Iterator<Item = &S> {
+        self.goals.iter().map(|&idx| &self.states[idx])
+    }
+
+    /// Number of distinct shortest paths from the start to any of the goals.
+    /// `N` can be any integer type, including big integers for huge graphs.
+    pub fn count_paths<N>(&self) -> N
+    where
+        N: Clone + From<u8> + Add<Output = N>,
+    {
+        let mut counts: Vec<Option<N>> = vec![None; self.states.len()];
+        counts[self.start] = Some(N::from(1));
+
+        // Iterative post-order visit, a node count is the sum of its predecessors counts
+        let mut stack: Vec<usize> = self.goals.clone();
+        while let Some(&idx) = stack.last() {
+            if counts[idx].is_some() {
+                stack.pop();
+                continue;
+            }
+
+            let missing: Vec<usize> = self.predecessors[idx]
+                .iter()
+                .filter(|&&p| counts[p].is_none())
+                .copied()
+                .collect();
+
+            if missing.is_empty() {
+                let count = self.predecessors[idx]
+                    .iter()
+                    .fold(N::from(0), |acc, &p| acc + counts[p].clone().unwrap());
+                counts[idx] = Some(count);
+                stack.pop();
+            } else {
+                stack.extend(missing);
+            }
+        }
+
+        self.goals
+            .iter()
+            .fold(N::from(0), |acc, &goal| acc + counts[goal].clone().unwrap())
+    }
+
+    /// Lazily enumerates every shortest path, from the start to a goal.
+    pub fn paths(&self) -> Paths<'_, S, C> {
+        Paths {
+            dag: self,
+            goals: self.goals.iter(),
+            stack: vec![],
+        }
+    }
+
+    /// All the states lying on at least one shortest path.
+    pub fn nodes(&self) -> HashSet<S> {
+        self.on_paths()
+            .map(|idx| self.states[idx].clone())
+            .collect()
+    }
+
+    /// All the moves `(from, to)` taken by at least one shortest path.
+    pub fn edges(&self) -> HashSet<(S, S)> {
+        self.on_paths()
+            .flat_map(|idx| {
+                self.predecessors[idx]
+                    .iter()
+                    .map(move |&p| (self.states[p].clone(), self.states[idx].clone()))
+            })
+            .collect()
+    }
+
+    fn on_paths(&self) -> impl Iterator<Item = usize> {
+        let mut seen = vec![false; self.states.len()];
+        let mut stack = self.goals.clone();
+        while let Some(idx) = stack.pop() {
+            if !seen[idx] {
+                seen[idx] = true;
+                stack.extend(self.predecessors[idx].iter().copied());
+            }
+        }
+        (0..self.states.len()).filter(move |&idx| seen[idx])
+    }
+}
+
+/// Iterator over the paths of a [`ShortestPathDag`], see [`ShortestPathDag::paths`].
+pub struct Paths<'a, S, C> {
+    dag: &'a ShortestPathDag<S, C>,
+    goals: std::slice::Iter<'a, usize>,
+    // Current path walked backwards from a goal, each state with the
+    // index of the next predecessor to explore
+    stack: Vec<(usize, usize)>,
+}
+
+impl<S, C> Iterator for Paths<'_, S, C>
+where
+    S: Clone,
+{
+    type Item = Vec<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(&(idx, next)) = self.stack.last() else {
+                let &goal = self.goals.next()?;
+                self.stack.push((goal, 0));
+                continue;
+            };
+
+            if idx == self.dag.start {
+                let path = self
+                    .stack
+                    .iter()
+                    .rev()
+                    .map(|&(idx, _)| self.dag.states[idx].clone())
+                    .collect();
+                self.stack.pop();
+                return Some(path);
+            }
+
+            match self.dag.predecessors[idx].get(next) {
+                Some(&pred) => {
+                    self.stack.last_mut().unwrap().1 += 1;
+                    self.stack.push((pred, 0));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Dijkstra search recording all the shortest paths from `start` to the
+/// cheapest states matching `is_goal`, instead of a single one.
+/// Move costs must be strictly positive, so that predecessors can't form cycles.
+pub fn dijkstra_dag<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<ShortestPathDag<S, C>>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut states = vec![start.clone()];
+    let mut costs = vec![C::default()];
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]];
+    let mut indices = HashMap::from([(start, 0)]);
+
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut goals = vec![];
+    let mut best = None;
+
+    while let Some(Reverse((cost, idx))) = heap.pop() {
+        if cost > costs[idx] || goals.contains(&idx) {
+            continue;
+        }
+
+        // Keep popping after the first goal, to collect the other goals with the same cost
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+
+        let state = states[idx].clone();
+        if is_goal(&state) {
+            best = Some(cost);
+            goals.push(idx);
+            continue;
+        }
+
+        for (next, move_cost) in successors(&state) {
+            let new_cost = cost + move_cost;
+            match indices.entry(next) {
+                Entry::Vacant(entry) => {
+                    states.push(entry.key().clone());
+                    entry.insert(states.len() - 1);
+                    costs.push(new_cost);
+                    predecessors.push(vec![idx]);
+                    heap.push(Reverse((new_cost, states.len() - 1)));
+                }
+                Entry::Occupied(entry) => {
+                    let next_idx = *entry.get();
+                    if new_cost < costs[next_idx] {
+                        costs[next_idx] = new_cost;
+                        predecessors[next_idx] = vec![idx];
+                        heap.push(Reverse((new_cost, next_idx)));
+                    } else if new_cost == costs[next_idx] && !predecessors[next_idx].contains(&idx)
+                    {
+                        predecessors[next_idx].push(idx);
+                    }
+                }
+            }
+        }
+    }
+
+    Some(ShortestPathDag {
+        cost: best?,
+        start: 0,
+        goals,
+        states,
+        predecessors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small weighted graph where the direct edge is not the cheapest path
+    fn edges(node: &u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 2), (0, 1)],
+            2 => vec![(3, 3)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let result = bfs(1u32, |n| [n + 1, n * 2], |n| *n == 10).unwrap();
+        assert_eq!(4, result.cost);
+        assert_eq!(vec![1, 2, 4, 5, 10], result.path);
+    }
+
+    #[test]
+    fn test_bfs_unreachable() {
+        assert_eq!(None, bfs(0u8, |_| None, |n| *n == 1));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra(0u8, edges, |n| *n == 3).unwrap();
+        assert_eq!(6, result.cost);
+        assert_eq!(vec![0, 1, 2, 3], result.path);
+    }
+
+    #[test]
+    fn test_astar() {
+        let target = (4i32, 3i32);
+        let result = astar(
+            (0i32, 0i32),
+            |&(y, x)| {
+                [(1, 0), (0, 1), (-1, 0), (0, -1)]
+                    .map(|(dy, dx)| ((y + dy, x + dx), 1))
+                    .into_iter()
+                    .filter(|((y, x), _)| (0..5).contains(y) && (0..5).contains(x))
+            },
+            |&(y, x)| (target.0 - y).abs() + (target.1 - x).abs(),
+            |p| *p == target,
+        )
+        .unwrap();
+        assert_eq!(7, result.cost);
+        assert_eq!(8, result.path.len());
+    }
+
+    // Diamond graph, two equal-cost ways from 0 to 3 and a more expensive one
+    fn diamond(node: &u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 1), (5, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_dag() {
+        let dag = dijkstra_dag(0u8, diamond, |n| *n == 3).unwrap();
+        assert_eq!(2, dag.cost());
+        assert_eq!(2u64, dag.count_paths());
+        assert_eq!(HashSet::from([0, 1, 2, 3]), dag.nodes());
+        assert_eq!(HashSet::from([(0, 1), (0, 2), (1, 3), (2, 3)]), dag.edges());
+
+        let mut paths: Vec<Vec<u8>> = dag.paths().collect();
+        paths.sort();
+        assert_eq!(vec![vec![0, 1, 3], vec![0, 2, 3]], paths);
+    }
+
+    #[test]
+    fn test_dijkstra_dag_multiple_goals() {
+        let dag = dijkstra_dag(0u8, diamond, |n| *n >= 4).unwrap();
+        assert_eq!(3, dag.cost());
+        assert_eq!(4u128, dag.count_paths());
+        assert_eq!(4, dag.paths().count());
+        assert_eq!(HashSet::from([&4, &5]), dag.goals().collect());
+    }
+
+    #[test]
+    fn test_count_paths_grows_exponentially() {
+        // Chain of 100 diamonds, 2^100 shortest paths
+        let dag = dijkstra_dag(
+            0u32,
+            |&n| [(2 * (n / 2) + 2, 1), (2 * (n / 2) + 3, 1)],
+            |&n| n >= 200,
+        )
+        .unwrap();
+        assert_eq!(100, dag.cost());
+        assert_eq!(1u128 << 100, dag.count_paths::<u128>());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::VecDeque, fmt::Debug, hash::Hash};
+
+use crate::cycle::{self, Cycle};
+
+/// Where the machine goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Next,
+    Jump(usize),
+    Halt,
+}
+
+/// Instructions of a machine: how they are read from the program, how long they
+/// take and what they do.
+pub trait InstructionSet: Sized {
+    /// Element of the program, like a byte or a parsed line.
+    type Word: Clone + Debug + PartialEq + Eq;
+    type Instruction;
+    type Registers: Clone + Debug + PartialEq + Eq + Hash;
+    type Output: Clone + Debug + PartialEq + Eq;
+
+    /// Instruction starting at `ip` with the number of words it takes, `None` when
+    /// there is none, which halts the machine.
+    fn decode(program: &[Self::Word], ip: usize) -> Option<(Self::Instruction, usize)>;
+
+    /// Number of cycles the instruction takes.
+    fn cycles(_instruction: &Self::Instruction) -> usize {
+        1
+    }
+
+    /// Runs the instruction, once all its cycles are done.
+    fn execute(instruction: &Self::Instruction, machine: &mut Machine<Self>) -> Flow;
+}
+
+/// Machine running a program with the instructions of `I`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine<I: InstructionSet> {
+    pub program: Vec<I::Word>,
+    pub registers: I::Registers,
+    pub ip: usize,
+    pub output: VecDeque<I::Output>,
+    /// Number of cycles run so far.
+    pub cycle: usize,
+    pub halted: bool,
+}
+
+impl<I: InstructionSet> Machine<I> {
+    pub fn new(program: Vec<I::Word>, registers: I::Registers) -> Self {
+        Self {
+            program,
+            registers,
+            ip: 0,
+            output: VecDeque::new(),
+            cycle: 0,
+            halted: false,
+        }
+    }
+
+    /// Runs the next instruction, `false` once the machine has halted.
+    pub fn step(&mut self) -> bool {
+        self.step_with(|_, _| {})
+    }
+
+    /// Same as [Machine::step], calling `during` for every cycle of the instruction
+    /// with the cycle number (from 1) and the machine as it is during that cycle,
+    /// before the instruction changes it.
+    pub fn step_with(&mut self, mut during: impl FnMut(usize, &Self)) -> bool {
+        if self.halted {
+            return false;
+        }
+        let Some((instruction, size)) = I::decode(&self.program, self.ip) else {
+            self.halted = true;
+            return false;
+        };
+
+        for _ in 0..I::cycles(&instruction) {
+            self.cycle += 1;
+            during(self.cycle, self);
+        }
+
+        match I::execute(&instruction, self) {
+            Flow::Next => self.ip += size,
+            Flow::Jump(ip) => self.ip = ip,
+            Flow::Halt => self.halted = true,
+        }
+        true
+    }
+
+    /// Runs until the machine halts.
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Runs until the machine halts, calling `during` for every cycle.
+    pub fn run_with(&mut self, mut during: impl FnMut(usize, &Self)) {
+        while self.step_with(&mut during) {}
+    }
+
+    /// Runs until the machine comes back to the same instruction with the same
+    /// registers, which means it loops forever, or `None` when it halts. The cycle
+    /// is counted in instructions from the current one.
+    pub fn find_loop(&mut self) -> Option<Cycle> {
+        cycle::detect(
+            self,
+            |machine| machine.step(),
+            |machine| (machine.ip, machine.registers.clone()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counter machine: increments its register, outputs it, jumps or stops
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Op {
+        Inc(u32),
+        Out,
+        Jmp(usize),
+        Jlt(u32, usize),
+        Hlt,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Counter;
+
+    impl InstructionSet for Counter {
+        type Word = Op;
+        type Instruction = Op;
+        type Registers = u32;
+        type Output = u32;
+
+        fn decode(program: &[Op], ip: usize) -> Option<(Op, usize)> {
+            program.get(ip).map(|op| (op.clone(), 1))
+        }
+
+        fn cycles(instruction: &Op) -> usize {
+            match instruction {
+                Op::Inc(_) => 2,
+                _ => 1,
+            }
+        }
+
+        fn execute(instruction: &Op, machine: &mut Machine<Self>) -> Flow {
+            match *instruction {
+                Op::Inc(n) => machine.registers = (machine.registers + n) % 10,
+                Op::Out => machine.output.push_back(machine.registers),
+                Op::Jmp(ip) => return Flow::Jump(ip),
+                Op::Jlt(n, ip) if machine.registers < n => return Flow::Jump(ip),
+                Op::Jlt(_, _) => {}
+                Op::Hlt => return Flow::Halt,
+            }
+            Flow::Next
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let program = vec![Op::Inc(3), Op::Out, Op::Jlt(7, 0), Op::Hlt, Op::Out];
+        let mut machine: Machine<Counter> = Machine::new(program, 0);
+
+        let mut during = vec![];
+        machine.run_with(|cycle, m| during.push((cycle, m.registers)));
+
+        assert!(machine.halted);
+        assert_eq!(vec![3, 6, 9], Vec::from(machine.output.clone()));
+        assert_eq!(3, machine.ip);
+        assert_eq!(13, machine.cycle);
+        // Both cycles of an increment see the old value
+        assert_eq!(vec![(1, 0), (2, 0), (3, 3), (4, 3), (5, 3)], during[..5]);
+        assert!(!machine.step());
+    }
+
+    #[test]
+    fn test_find_loop() {
+        let program = vec![Op::Inc(4), Op::Jmp(0)];
+        let mut machine: Machine<Counter> = Machine::new(program, 0);
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                length: 10
+            }),
+            machine.find_loop()
+        );
+
+        let mut halting: Machine<Counter> = Machine::new(vec![Op::Inc(1), Op::Out], 0);
+        assert_eq!(None, halting.find_loop());
+        assert!(halting.halted);
+    }
+}
@@ -0,0 +1,340 @@
+//! `#[derive(AocParse)]` generates `FromStr` from the format of the input lines.
+//!
+//! ```ignore
+//! #[derive(AocParse)]
+//! #[aoc(format = "p={x},{y} v={dx},{dy}")]
+//! struct Robot {
+//!     x: i32,
+//!     y: i32,
+//!     dx: i32,
+//!     dy: i32,
+//! }
+//! ```
+//!
+//! - `{name}` is a field of the struct (`{0}` for tuple structs), parsed with its
+//!   own `FromStr`, so it can be another derived type. Fields missing from the
+//!   format get their `Default` value.
+//! - `{name:sep}` splits the value by `sep` and collects the parsed items, e.g.
+//!   `{items:,}` for `79, 98`.
+//! - `{_}` skips a value.
+//! - Any whitespace in the format matches any amount of whitespace, including
+//!   newlines, so a format can span several lines.
+//! - A field ends where the text following it in the format starts, two fields
+//!   must be separated by some text.
+//! - `{{` and `}}` are literal braces.
+//!
+//! Every variant of an enum has its own format, the first one matching is used.
+//! The generated code relies on `aoc_common::parse::Cursor`, and errors are
+//! `aoc_common::parse::ParseError` with the line and column of the mismatch. Use
+//! `#[aoc(crate = "path")]` on the type when `aoc_common` is under another path.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, LitStr, Path, Result};
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Field {
+        name: String,
+        separator: Option<String>,
+    },
+}
+
+fn parse_format(format: &str) -> std::result::Result<Vec<Segment>, String> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("Unclosed `{` in format".to_string()),
+                    }
+                }
+
+                let (name, separator) = match spec.split_once(':') {
+                    Some((name, separator)) => (name.trim(), Some(separator.to_string())),
+                    None => (spec.trim(), None),
+                };
+                if name.is_empty() {
+                    return Err("Missing field name in `{}`".to_string());
+                }
+
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                } else if let Some(Segment::Field { name: previous, .. }) = segments.last() {
+                    return Err(format!(
+                        "Fields `{}` and `{}` must be separated by some text",
+                        previous, name
+                    ));
+                }
+                segments.push(Segment::Field {
+                    name: name.to_string(),
+                    separator,
+                });
+            }
+            '}' => return Err("Unmatched `}` in format, use `}}` for a literal one".to_string()),
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+// Options of the `#[aoc(...)]` attributes
+#[derive(Default)]
+struct Options {
+    format: Option<LitStr>,
+    krate: Option<Path>,
+}
+
+fn parse_options(attrs: &[Attribute]) -> Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                options.format = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("crate") {
+                let path: LitStr = meta.value()?.parse()?;
+                options.krate = Some(path.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "Unsupported attribute, expected `format = \"...\"` or `crate = \"...\"`",
+                ))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let options = parse_options(&input.attrs)?;
+    let krate = match &options.krate {
+        Some(path) => quote!(#path),
+        None => quote!(::aoc_common),
+    };
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = options.format.ok_or_else(|| {
+                Error::new(name.span(), "Missing #[aoc(format = \"...\")] attribute")
+            })?;
+            parse_fields(&format, &data.fields, quote!(Self), &krate)?
+        }
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                return Err(Error::new(
+                    name.span(),
+                    "Cannot parse an enum without variants",
+                ));
+            }
+
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let format = parse_options(&variant.attrs)?.format.ok_or_else(|| {
+                        Error::new(
+                            variant.ident.span(),
+                            "Missing #[aoc(format = \"...\")] attribute on variant",
+                        )
+                    })?;
+                    let ident = &variant.ident;
+                    parse_fields(&format, &variant.fields, quote!(Self::#ident), &krate)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // Keep the error of the variant which matched the longest part of the input
+            quote! {
+                let mut error: ::std::option::Option<Self::Err> = None;
+                #(
+                    let result: ::std::result::Result<Self, Self::Err> =
+                        (|| #variants)();
+                    match result {
+                        Ok(value) => return Ok(value),
+                        Err(e) => {
+                            if error
+                                .as_ref()
+                                .is_none_or(|old| (e.line, e.column) > (old.line, old.column))
+                            {
+                                error = Some(e);
+                            }
+                        }
+                    }
+                )*
+                Err(error.unwrap())
+            }
+        }
+        Data::Union(_) => return Err(Error::new(name.span(), "Cannot parse a union")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = #krate::parse::ParseError;
+
+            #[allow(clippy::redundant_closure_call)]
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #body
+            }
+        }
+    })
+}
+
+// Block parsing `s` against `format`, evaluating to a `Result` of the value built
+// with `constructor`.
+fn parse_fields(
+    format: &LitStr,
+    fields: &Fields,
+    constructor: TokenStream2,
+    krate: &TokenStream2,
+) -> Result<TokenStream2> {
+    let segments = parse_format(&format.value()).map_err(|e| Error::new(format.span(), e))?;
+
+    // Name used in the format for every field, with its variable
+    let members: Vec<(String, syn::Ident, &syn::Type)> = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| match &field.ident {
+            Some(ident) => (
+                ident.to_string(),
+                format_ident!("field_{}", ident),
+                &field.ty,
+            ),
+            None => (idx.to_string(), format_ident!("field_{}", idx), &field.ty),
+        })
+        .collect();
+
+    let mut used: Vec<&str> = vec![];
+    let mut statements = vec![];
+    for (idx, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Text(text) => statements.push(quote!(cursor.pattern(#text)?;)),
+            Segment::Field { name, separator } => {
+                let next = match segments.get(idx + 1) {
+                    Some(Segment::Text(text)) => text.as_str(),
+                    _ => "",
+                };
+
+                if name == "_" {
+                    statements.push(quote!(cursor.field::<String>("_", #next)?;));
+                    continue;
+                }
+
+                let Some((_, var, ty)) = members.iter().find(|(n, _, _)| n == name) else {
+                    return Err(Error::new(
+                        format.span(),
+                        format!("Unknown field `{}` in format", name),
+                    ));
+                };
+                if used.contains(&name.as_str()) {
+                    return Err(Error::new(
+                        format.span(),
+                        format!("Field `{}` appears twice in format", name),
+                    ));
+                }
+                used.push(name);
+
+                statements.push(match separator {
+                    Some(separator) => {
+                        quote!(let #var: #ty = cursor.field_list(#name, #separator, #next)?;)
+                    }
+                    None => quote!(let #var: #ty = cursor.field(#name, #next)?;),
+                });
+            }
+        }
+    }
+
+    let values = members.iter().map(|(name, var, _)| {
+        if used.contains(&name.as_str()) {
+            quote!(#var)
+        } else {
+            quote!(::std::default::Default::default())
+        }
+    });
+    let value = match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote!(#constructor { #(#idents: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#constructor(#(#values),*)),
+        Fields::Unit => quote!(#constructor),
+    };
+
+    Ok(quote! {{
+        let mut cursor = #krate::parse::Cursor::new(s);
+        #(#statements)*
+        cursor.end()?;
+        Ok(#value)
+    }})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, separator: Option<&str>) -> Segment {
+        Segment::Field {
+            name: name.to_string(),
+            separator: separator.map(|s| s.to_string()),
+        }
+    }
+
+    fn text(text: &str) -> Segment {
+        Segment::Text(text.to_string())
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            Ok(vec![
+                text("p="),
+                field("x", None),
+                text(","),
+                field("y", None),
+                text(" v={"),
+                field("items", Some(",")),
+                text("}"),
+            ]),
+            parse_format("p={x},{y} v={{{items:,}}}")
+        );
+        assert_eq!(Ok(vec![field("0", None)]), parse_format("{0}"));
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        assert!(parse_format("{x}{y}").is_err());
+        assert!(parse_format("p={x").is_err());
+        assert!(parse_format("p=x}").is_err());
+        assert!(parse_format("p={}").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use aoc_derive::AocParse;
+
+#[derive(Debug, Clone, PartialEq, AocParse)]
+#[aoc(format = "p={x},{y} v={dx},{dy}")]
+struct Robot {
+    x: i32,
+    y: i32,
+    dx: i32,
+    dy: i32,
+}
+
+impl Display for Robot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "p={},{} v={},{}", self.x, self.y, self.dx, self.dy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, AocParse)]
+enum Operand {
+    #[aoc(format = "old")]
+    Old,
+    #[aoc(format = "{0}")]
+    Value(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, AocParse)]
+#[aoc(format = "Monkey {_}:
+  Starting items: {items:,}
+  Operation: new = old {operation} {operand}")]
+struct Monkey {
+    items: Vec<u64>,
+    operation: char,
+    operand: Operand,
+    // Not in the format
+    inspections: usize,
+}
+
+// `aoc_common` re-exported under another path
+mod shared {
+    pub use aoc_common::parse;
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(crate = "crate::shared", format = "{name}: value={value}")]
+struct Entry {
+    name: String,
+    value: u32,
+}
+
+#[test]
+fn test_round_trip() {
+    let robot = Robot {
+        x: 0,
+        y: 4,
+        dx: 3,
+        dy: -3,
+    };
+    assert_eq!(Ok(robot.clone()), robot.to_string().parse());
+    assert_eq!(Ok(Operand::Old), "old".parse());
+    assert_eq!(Ok(Operand::Value(19)), "19".parse());
+
+    let monkey = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * old";
+    assert_eq!(
+        Ok(Monkey {
+            items: vec![79, 98],
+            operation: '*',
+            operand: Operand::Old,
+            inspections: 0,
+        }),
+        monkey.parse()
+    );
+}
+
+#[test]
+fn test_crate_path() {
+    // The name goes on until the whole `: value=` matches, not at its first `:`
+    assert_eq!(
+        Ok(Entry {
+            name: "a: b".to_string(),
+            value: 3,
+        }),
+        "a: b: value=3".parse()
+    );
+}
+
+#[test]
+fn test_errors() {
+    let error = "p=0,4 v=3,x".parse::<Robot>().unwrap_err();
+    assert_eq!((1, 11), (error.line, error.column));
+    let error = "p=0,4 w=3,3".parse::<Robot>().unwrap_err();
+    assert_eq!((1, 7), (error.line, error.column));
+    assert!("p=0,4 v=3,3 extra".parse::<Robot>().is_err());
+
+    // The error of the variant going the furthest
+    let error = "olf".parse::<Operand>().unwrap_err();
+    assert_eq!((1, 3), (error.line, error.column));
+}